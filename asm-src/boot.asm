@@ -1,4 +1,4 @@
-%define STACKSIZE 8192
+%define STACKSIZE 65536
 
 global start
 extern long_mode_start
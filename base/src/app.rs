@@ -22,6 +22,21 @@ pub enum ProgramError {
     Custom(&'static str),
     Crash,
 }
+impl ProgramError {
+    /// The status code `run` pushes when an app fails with this error, 0 means success
+    ///
+    /// Scripts depend on these, so existing codes never change
+    pub fn code(&self) -> isize {
+        match self {
+            Self::InvalidStartParameter => 1,
+            Self::InvalidParameter => 2,
+            Self::FileSystemError => 3,
+            Self::InternalError => 4,
+            Self::Custom(_) => 5,
+            Self::Crash => 6,
+        }
+    }
+}
 pub struct OsHandle {
     fm: Option<*mut ForthMachine>,
     control_flow: ControlFlow,
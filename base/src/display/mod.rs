@@ -2,6 +2,8 @@ mod buffer;
 mod formatter;
 pub mod macros;
 pub mod primitives;
+mod scrollback;
+pub use scrollback::SCROLLBACK_LINES;
 mod universal;
 pub use universal::*;
 mod vga;
@@ -0,0 +1,102 @@
+use alloc::vec::Vec;
+
+use super::{DefaultVgaBuffer, VgaChar};
+
+/// How many lines which scrolled off the top of the screen are kept
+pub const SCROLLBACK_LINES: usize = 2000;
+
+pub(crate) type Line = [VgaChar; DefaultVgaBuffer::BUFFER_WIDTH];
+type Screen = [Line; DefaultVgaBuffer::BUFFER_HEIGHT];
+
+/// The scrollback of the text screen, shared by every writer
+///
+/// Consoles which aren't shown keep their lines in their saved screen
+pub(crate) static mut SCROLLBACK: Scrollback = Scrollback::new();
+
+/// Lines which have scrolled off the top of the text screen, kept in a ring buffer
+///
+/// This is a static array rather than a heap allocation since text is
+/// written to the screen before the allocator exists
+pub(crate) struct Scrollback {
+    lines: [Line; SCROLLBACK_LINES],
+    first: usize,
+    len: usize,
+    /// How many lines up from the bottom the screen is showing, 0 when showing live output
+    offset: usize,
+    /// What the screen showed before scrolling up
+    live: Screen,
+}
+
+impl Scrollback {
+    const fn new() -> Self {
+        Self {
+            lines: [[VgaChar::BLANK; DefaultVgaBuffer::BUFFER_WIDTH]; SCROLLBACK_LINES],
+            first: 0,
+            len: 0,
+            offset: 0,
+            live: [[VgaChar::BLANK; DefaultVgaBuffer::BUFFER_WIDTH];
+                DefaultVgaBuffer::BUFFER_HEIGHT],
+        }
+    }
+
+    pub(crate) fn push(&mut self, line: Line) {
+        if self.len < SCROLLBACK_LINES {
+            self.lines[(self.first + self.len) % SCROLLBACK_LINES] = line;
+            self.len += 1;
+        } else {
+            self.lines[self.first] = line;
+            self.first = (self.first + 1) % SCROLLBACK_LINES;
+        }
+    }
+
+    /// Shows the screen as it was `offset` lines of output ago
+    pub(crate) fn view(&mut self, screen: &mut DefaultVgaBuffer, offset: usize) {
+        let offset = offset.min(self.len);
+        if offset == self.offset {
+            return;
+        }
+        if self.offset == 0 {
+            self.live = screen.chars;
+        }
+        self.offset = offset;
+        for (row, line) in screen.chars.iter_mut().enumerate() {
+            let index = self.len + row - offset;
+            *line = match index < self.len {
+                true => self.lines[(self.first + index) % SCROLLBACK_LINES],
+                false => self.live[index - self.len],
+            };
+        }
+    }
+
+    /// Takes the kept lines, oldest first, leaving the scrollback empty
+    pub(crate) fn take_lines(&mut self) -> Vec<Line> {
+        let lines = (0..self.len)
+            .map(|index| self.lines[(self.first + index) % SCROLLBACK_LINES])
+            .collect();
+        self.first = 0;
+        self.len = 0;
+        lines
+    }
+
+    /// Keeps these lines instead, oldest first
+    pub(crate) fn set_lines(&mut self, lines: &[Line]) {
+        self.first = 0;
+        self.len = 0;
+        for line in lines {
+            self.push(*line);
+        }
+    }
+
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Goes back to showing live output, which is done before anything new is written
+    #[inline]
+    pub(crate) fn snap_back(&mut self, screen: &mut DefaultVgaBuffer) {
+        if self.offset != 0 {
+            screen.chars = self.live;
+            self.offset = 0;
+        }
+    }
+}
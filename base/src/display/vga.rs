@@ -1,8 +1,12 @@
+use alloc::{string::String, vec::Vec};
 use core::ops::{Deref, DerefMut};
 
 use x86_64::instructions::port::{Port, PortWriteOnly};
 
-use super::{KernelDebug, KernelFormatter, ScreenBuffer, VgaPalette};
+use super::{
+    scrollback::{Line, SCROLLBACK},
+    KernelDebug, KernelFormatter, ScreenBuffer, VgaPalette,
+};
 
 const DEFAULT_VGA_BUFFER_WIDTH: usize = 80;
 const DEFAULT_VGA_BUFFER_HEIGHT: usize = 25;
@@ -35,6 +39,7 @@ pub struct DefaultVgaWriter {
     position: (usize, usize),
     fallback_color: VgaColorCombo,
     cursor: bool,
+    capture: Option<String>,
 }
 
 impl DefaultVgaWriter {
@@ -82,6 +87,29 @@ impl DefaultVgaWriter {
         }
         self
     }
+    pub fn save_screen(&mut self) -> SavedScreen {
+        unsafe { SCROLLBACK.snap_back(self.buffer) };
+        let cursor = unsafe {
+            let mut d5 = Port::<u8>::new(0x03D5_u16);
+            PortWriteOnly::new(0x03D4_u16).write(0x0F_u8);
+            let low = d5.read();
+            PortWriteOnly::new(0x03D4_u16).write(0x0E_u8);
+            let high = d5.read();
+            u16::from_le_bytes([low, high])
+        };
+        SavedScreen {
+            chars: self.buffer.chars,
+            cursor,
+            scrollback: unsafe { SCROLLBACK.take_lines() },
+        }
+    }
+    pub fn restore_screen(&mut self, screen: &SavedScreen) -> &mut Self {
+        unsafe { SCROLLBACK.snap_back(self.buffer) };
+        self.buffer.chars = screen.chars;
+        unsafe { SCROLLBACK.set_lines(&screen.scrollback) };
+        let width = self.buffer.width() as u16;
+        self.update_cursor((screen.cursor % width) as u8, (screen.cursor / width) as u8)
+    }
     pub fn read_palette(&mut self) -> VgaPalette<256> {
         crate::read_vga_palette()
     }
@@ -108,8 +136,15 @@ impl DefaultVgaWriter {
             position: (0, 0),
             fallback_color: VgaColorCombo::new(VgaColor::White, VgaColor::Black),
             cursor: false,
+            capture: None,
         }
     }
+    /// Collects written text in a string instead of showing it, until capturing is replaced with None
+    ///
+    /// Returns what was captured before, so captures can be nested
+    pub fn capture_output(&mut self, capture: Option<String>) -> Option<String> {
+        core::mem::replace(&mut self.capture, capture)
+    }
     pub fn set_palette<const N: usize>(&mut self, palette: VgaPalette<N>) {
         crate::switch_vga_palette(palette)
     }
@@ -119,6 +154,26 @@ impl DefaultVgaWriter {
         self.write_str(text);
         self
     }
+    /// Shows older output which has scrolled off the top of the screen
+    ///
+    /// Writing anything goes back to showing live output
+    pub fn scroll_view_up(&mut self, lines: usize) -> &mut Self {
+        unsafe {
+            let offset = SCROLLBACK.offset() + lines;
+            SCROLLBACK.view(self.buffer, offset);
+        }
+        self
+    }
+    pub fn scroll_view_down(&mut self, lines: usize) -> &mut Self {
+        unsafe {
+            let offset = SCROLLBACK.offset().saturating_sub(lines);
+            SCROLLBACK.view(self.buffer, offset);
+        }
+        self
+    }
+    pub fn get_default_colors(&mut self) -> VgaColorCombo {
+        self.fallback_color
+    }
     pub fn set_default_colors(&mut self, color: VgaColorCombo) -> &mut Self {
         self.fallback_color = color;
         self
@@ -141,6 +196,10 @@ impl DefaultVgaWriter {
         self
     }
     pub fn write_unicode_char(&mut self, char: char) -> &mut Self {
+        if let Some(capture) = &mut self.capture {
+            capture.push(char);
+            return self;
+        }
         let byte = match char {
             'å' => 0x86,
             'ä' => 0x84,
@@ -174,6 +233,10 @@ impl DefaultVgaWriter {
         self.write_debugable(byte);
     }
     pub fn next_line(&mut self) -> &mut Self {
+        if let Some(capture) = &mut self.capture {
+            capture.push('\n');
+            return self;
+        }
         let (col, row) = &mut self.position;
         *col = 0;
         *row += 1;
@@ -185,6 +248,11 @@ impl DefaultVgaWriter {
         self
     }
     pub fn write_char(&mut self, char: VgaChar) {
+        if let Some(capture) = &mut self.capture {
+            capture.push(char::from(char.char));
+            return;
+        }
+        unsafe { SCROLLBACK.snap_back(self.buffer) };
         match char.char {
             b'\n' => {
                 self.next_line();
@@ -205,7 +273,12 @@ impl DefaultVgaWriter {
             self.update_cursor(self.position.0 as u8, self.position.1 as u8);
         }
     }
+    /// Scrolls the screen up one line, keeping the top line in the scrollback
     pub fn prepare_print(&mut self) {
+        unsafe {
+            SCROLLBACK.snap_back(self.buffer);
+            SCROLLBACK.push(self.buffer.chars[0]);
+        }
         let last_line = self.buffer.height() - 1;
         self.position = (0, last_line);
         for i in 1..=last_line {
@@ -215,6 +288,15 @@ impl DefaultVgaWriter {
     }
 }
 
+/// What the text screen showed, where its cursor was and its scrollback, to show it again later
+///
+/// Saving takes the scrollback, so the screen shown next starts with an empty one
+pub struct SavedScreen {
+    chars: [[VgaChar; DEFAULT_VGA_BUFFER_WIDTH]; DEFAULT_VGA_BUFFER_HEIGHT],
+    cursor: u16,
+    scrollback: Vec<Line>,
+}
+
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub struct VgaChar {
@@ -222,7 +304,7 @@ pub struct VgaChar {
     color: VgaColorCombo,
 }
 impl VgaChar {
-    const BLANK: Self = Self {
+    pub const BLANK: Self = Self {
         char: b' ',
         color: VgaColorCombo(0),
     };
@@ -230,8 +312,12 @@ impl VgaChar {
         self.color = color;
         self
     }
+    /// The character in code page 437
+    pub fn byte(self) -> u8 {
+        self.char
+    }
 }
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 #[repr(transparent)]
 pub struct VgaColorCombo(u8);
 impl VgaColorCombo {
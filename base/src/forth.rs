@@ -1,9 +1,27 @@
 use crate::display::{DefaultVgaWriter, UniversalVgaFormatter};
 use alloc::vec;
-use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
-use core::{arch::x86_64, fmt::Display};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::String,
+    sync::Arc,
+    vec::Vec,
+};
+use core::{
+    arch::x86_64,
+    fmt::Display,
+    sync::atomic::{AtomicBool, Ordering},
+};
 pub type ForthFunction = &'static (dyn Fn(&mut ForthMachine) + Sync + Send + 'static);
 
+/// Set by the keyboard interrupt when Ctrl+C is pressed, running forth aborts at the next instruction
+pub static INTERRUPT_REQUESTED: AtomicBool = AtomicBool::new(false);
+/// Set while forth runs a line, otherwise Ctrl+C is an ordinary key for whoever reads the keyboard
+pub static INTERRUPTIBLE: AtomicBool = AtomicBool::new(false);
+/// How deep user words may call each other, every call takes almost 1 KiB of the kernel stack
+/// which is STACKSIZE in boot.asm
+const MAX_CALL_DEPTH: usize = 32;
+
 fn forth_print(fm: &mut ForthMachine) {
     if let Some(x) = fm.stack.pop() {
         fm.formatter.write_str(&format!("{}", x));
@@ -56,7 +74,7 @@ fn forth_debug(fm: &mut ForthMachine) {
 }
 
 fn forth_new_word(fm: &mut ForthMachine) {
-    let mut defined_as = ForthInstructions::default();
+    let mut defined_as = ForthWord::default();
     let instruction_name =
         if let Some(ForthInstruction::Word(s)) = fm.instructions.get(fm.instruction_counter) {
             s.clone()
@@ -64,19 +82,39 @@ fn forth_new_word(fm: &mut ForthMachine) {
             return;
         };
     let mut instruction_counter = fm.instruction_counter + 1;
+    if let Some(ForthInstruction::Word(s)) = fm.instructions.get(instruction_counter) {
+        if s == "{:" {
+            match parse_locals(&fm.instructions, instruction_counter + 1) {
+                Some((locals, arguments, end)) => {
+                    defined_as.locals = locals;
+                    defined_as.arguments = arguments;
+                    instruction_counter = end;
+                }
+                None => {
+                    fm.error("unterminated locals declaration, expected :}");
+                    return;
+                }
+            }
+        }
+    }
     let mut complete_instruction = false;
     while let Some(instruction) = fm.instructions.get(instruction_counter) {
+        let location = fm.instructions.location(instruction_counter).clone();
         match instruction.clone() {
             ForthInstruction::Word(s) => {
                 if s == ":" {
                     complete_instruction = true;
                     break;
                 } else {
-                    defined_as.0.push(ForthInstruction::Word(s));
+                    defined_as
+                        .instructions
+                        .push(ForthInstruction::Word(s), location);
                 }
             }
             ForthInstruction::Data(x) => {
-                defined_as.0.push(ForthInstruction::Data(x));
+                defined_as
+                    .instructions
+                    .push(ForthInstruction::Data(x), location);
             }
         }
 
@@ -89,6 +127,37 @@ fn forth_new_word(fm: &mut ForthMachine) {
     }
 }
 
+/// Parses an ANS style locals declaration `{: a b | c -- d :}` starting right after the `{:`
+///
+/// Returns the local names, how many of them are taken from the stack and the index after `:}`
+fn parse_locals(
+    instructions: &ForthInstructions,
+    mut instruction_counter: usize,
+) -> Option<(Vec<String>, usize, usize)> {
+    let mut locals = Vec::new();
+    let mut arguments = None;
+    let mut outputs = false;
+    while let Some(instruction) = instructions.get(instruction_counter) {
+        instruction_counter += 1;
+        let ForthInstruction::Word(word) = instruction else {
+            continue;
+        };
+        match word.as_str() {
+            ":}" => {
+                let arguments = arguments.unwrap_or(locals.len());
+                return Some((locals, arguments, instruction_counter));
+            }
+            ":" => return None,
+            "|" => arguments = Some(locals.len()),
+            "--" => outputs = true,
+            // Everything after -- only documents the results
+            _ if outputs => (),
+            name => locals.push(String::from(name)),
+        }
+    }
+    None
+}
+
 fn forth_add(fm: &mut ForthMachine) {
     if let Some((x, y)) = fm.stack.try_pop_two_ints() {
         fm.stack.push(StackItem::Int(x + y));
@@ -132,10 +201,12 @@ fn forth_again(fm: &mut ForthMachine) {
         }
     }
 
-    fm.formatter
-        .write_str("\nERROR: AGAIN couldn't find any BEGIN word\n");
+    fm.error("AGAIN couldn't find any BEGIN word");
 }
 
+// BEGIN and THEN only mark where AGAIN and IF jump to
+fn forth_marker(_fm: &mut ForthMachine) {}
+
 fn forth_if(fm: &mut ForthMachine) {
     if let Some(0) = fm.stack.try_pop::<isize>() {
         let mut instruction_counter = fm.instruction_counter;
@@ -151,87 +222,376 @@ fn forth_if(fm: &mut ForthMachine) {
     }
 }
 
+fn forth_execute(fm: &mut ForthMachine) {
+    match fm.stack.try_pop::<ExecutionToken>() {
+        Some(xt) => fm.execute(xt),
+        None => {
+            fm.error("EXECUTE expects an execution token");
+        }
+    }
+}
+
+// n xt TIMES
+fn forth_times(fm: &mut ForthMachine) {
+    let Some((count, xt)) = fm.stack.try_pop_count_and_xt() else {
+        fm.error("TIMES expects a count and an execution token");
+        return;
+    };
+    for _ in 0..count {
+        if fm.is_aborted() {
+            return;
+        }
+        fm.execute(xt.clone());
+    }
+}
+
+// x1..xn n xt MAP -> y1..yn n
+fn forth_map(fm: &mut ForthMachine) {
+    let Some((items, xt)) = fm.stack.try_pop_items_and_xt() else {
+        fm.error("MAP expects items, a count and an execution token");
+        return;
+    };
+    let count = items.len() as isize;
+    let mut results = Vec::with_capacity(items.len());
+    for item in items {
+        if fm.is_aborted() {
+            return;
+        }
+        let depth = fm.stack.len();
+        fm.stack.push(item);
+        fm.execute(xt.clone());
+        if fm.stack.len() != depth + 1 {
+            fm.abort("MAP expects the execution token to leave exactly one item");
+            return;
+        }
+        results.extend(fm.stack.pop());
+    }
+    fm.stack.0.append(&mut results);
+    fm.stack.push(StackItem::Int(count));
+}
+
+// x1..xn n xt FILTER -> xi..xj m
+fn forth_filter(fm: &mut ForthMachine) {
+    let Some((items, xt)) = fm.stack.try_pop_items_and_xt() else {
+        fm.error("FILTER expects items, a count and an execution token");
+        return;
+    };
+    let mut kept = Vec::new();
+    for item in items {
+        if fm.is_aborted() {
+            return;
+        }
+        fm.stack.push(item.clone());
+        fm.execute(xt.clone());
+        if !matches!(fm.stack.try_pop::<isize>(), Some(0) | None) {
+            kept.push(item);
+        }
+    }
+    let count = kept.len() as isize;
+    fm.stack.0.append(&mut kept);
+    fm.stack.push(StackItem::Int(count));
+}
+
+// "source" EVALUATE
+fn forth_evaluate(fm: &mut ForthMachine) {
+    match fm.stack.try_pop::<String>() {
+        Some(source) => fm.run_source(&source, None),
+        None => fm.error("EVALUATE expects a string"),
+    }
+}
+
+// n BUDGET, limits how many instructions a line may run, 0 removes the limit
+fn forth_budget(fm: &mut ForthMachine) {
+    match fm.stack.try_pop::<isize>() {
+        Some(budget) if budget > 0 => fm.set_instruction_budget(Some(budget as usize)),
+        Some(_) => fm.set_instruction_budget(None),
+        None => fm.error("BUDGET expects a number of instructions"),
+    }
+}
+
+// "value" "NAME" SET, sets an environment variable of this session
+fn forth_set(fm: &mut ForthMachine) {
+    let Some(name) = fm.stack.try_pop::<String>() else {
+        fm.error("SET expects a value and a name");
+        return;
+    };
+    let Some(value) = fm.stack.try_pop::<String>() else {
+        fm.error("SET expects a value and a name");
+        return;
+    };
+    fm.set_variable(&name, &value);
+}
+
+// "NAME" GET, pushes the value of an environment variable, unset variables are empty
+fn forth_get(fm: &mut ForthMachine) {
+    match fm.stack.try_pop::<String>() {
+        Some(name) => {
+            let value = String::from(fm.variable(&name).unwrap_or_default());
+            fm.stack.push(StackItem::String(value));
+        }
+        None => fm.error("GET expects a name"),
+    }
+}
+
+// ENV, lists every environment variable
+fn forth_env(fm: &mut ForthMachine) {
+    let mut list = String::new();
+    for (name, value) in &fm.variables {
+        list += &format!("\n{name}={value}");
+    }
+    fm.formatter.write_str(&list);
+}
+
+/// A word passed around as a value
+#[derive(PartialEq, Debug, Clone)]
+pub enum ExecutionToken {
+    /// A named word, created with ' or [']
+    Word(String),
+    /// An anonymous quotation, created with [: ... ;]
+    Quotation(ForthInstructions),
+}
+impl Display for ExecutionToken {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "' {word}"),
+            Self::Quotation(instructions) => write!(f, "[: {instructions} ;]"),
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub enum StackItem {
     String(String),
     Int(isize),
+    ExecutionToken(ExecutionToken),
 }
 impl Display for StackItem {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::String(x) => x.fmt(f),
             Self::Int(i) => i.fmt(f),
+            Self::ExecutionToken(xt) => xt.fmt(f),
         }
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct ForthInstructions(Vec<ForthInstruction>);
+/// Where an instruction was read from
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceLocation {
+    pub file: Option<Arc<str>>,
+    pub line: usize,
+}
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{file}:{}", self.line),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
 
-impl Default for ForthInstructions {
-    fn default() -> Self {
-        Self(alloc::vec![])
+/// A series of instructions and the source location of each one
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ForthInstructions {
+    instructions: Vec<ForthInstruction>,
+    locations: Vec<SourceLocation>,
+}
+
+/// Displays the instructions as forth source which parses back into the same instructions
+impl Display for ForthInstructions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, instruction) in self.instructions.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            match instruction {
+                ForthInstruction::Data(StackItem::String(string)) => {
+                    write!(f, "\"{}\"", string.replace('"', "\\\""))?
+                }
+                ForthInstruction::Data(item) => item.fmt(f)?,
+                ForthInstruction::Word(word) => f.write_str(word)?,
+            }
+        }
+        Ok(())
     }
 }
+
 impl ForthInstructions {
-    pub fn add_instructions_to_end(&mut self, new_data: &Vec<char>) {
+    /// Parses the text and adds it at the end, `file` is remembered as the source of every instruction
+    pub fn add_instructions_to_end(&mut self, new_data: &Vec<char>, file: Option<Arc<str>>) {
+        let mut parsed_instructions = Self::parse(new_data, file);
+        self.instructions
+            .append(&mut parsed_instructions.instructions);
+        self.locations.append(&mut parsed_instructions.locations);
+    }
+
+    fn parse(new_data: &Vec<char>, file: Option<Arc<str>>) -> Self {
         let mut parsed_instructions = ForthInstructions::default();
         let mut i = 0;
         let mut word = String::new();
         let mut string_mode = false;
+        let mut line = 1;
+        let mut word_line = 1;
 
         while i < new_data.len() {
             let prev_char = if i > 0 { new_data[i - 1] } else { '\0' };
             let c = new_data[i];
+            let location = SourceLocation {
+                file: file.clone(),
+                line: word_line,
+            };
 
-            if c == ' ' && string_mode == false {
+            if c.is_whitespace() && string_mode == false {
                 // Word ends or data ends
                 // Parse word/data into ForthInstruction then
                 if word.len() > 0 {
-                    parsed_instructions.0.push(word.into());
+                    parsed_instructions.push(word.into(), location);
                     word = String::new();
                 }
             } else if i + 1 == new_data.len() && string_mode == false {
                 // Last word doesnt have space after it
+                if word.is_empty() {
+                    word_line = line;
+                }
                 word.push(c);
-                parsed_instructions.0.push(word.into());
+                let location = SourceLocation {
+                    file: file.clone(),
+                    line: word_line,
+                };
+                parsed_instructions.push(word.into(), location);
                 word = String::new();
             } else {
                 if c == '"' && prev_char != '\\' {
                     // String mode flips
                     string_mode = !string_mode;
-                    if string_mode == false {
+                    if string_mode {
+                        word_line = line;
+                    } else {
                         // Save it
                         parsed_instructions
-                            .0
-                            .push(ForthInstruction::Data(StackItem::String(word)));
+                            .push(ForthInstruction::Data(StackItem::String(word)), location);
                         word = String::new();
                     }
                 } else if c == '"' && prev_char == '\\' {
                     word.pop();
                     word.push(c);
                 } else {
+                    if word.is_empty() {
+                        word_line = line;
+                    }
                     word.push(c);
                 }
             }
 
             // \" Hello \" "df
 
+            if c == '\n' {
+                line += 1;
+            }
             i += 1;
         }
-        self.0.append(&mut parsed_instructions.0);
+        parsed_instructions.fold_execution_tokens()
+    }
+
+    /// Turns ' name, ['] name and [: ... ;] into execution token literals
+    fn fold_execution_tokens(self) -> Self {
+        // Each open quotation remembers where its [: was
+        let mut quotations = alloc::vec![(Self::default(), SourceLocation::default())];
+        let mut instructions = self.instructions.into_iter().zip(self.locations);
+        while let Some((instruction, location)) = instructions.next() {
+            let nested = quotations.len() > 1;
+            let (current, _) = quotations.last_mut().unwrap();
+            match instruction {
+                ForthInstruction::Word(word) if word == "'" || word == "[']" => {
+                    match instructions.next() {
+                        Some((ForthInstruction::Word(name), _)) => current.push(
+                            ForthInstruction::Data(StackItem::ExecutionToken(
+                                ExecutionToken::Word(name),
+                            )),
+                            location,
+                        ),
+                        Some((other, other_location)) => {
+                            current.push(ForthInstruction::Word(word), location);
+                            current.push(other, other_location);
+                        }
+                        None => current.push(ForthInstruction::Word(word), location),
+                    }
+                }
+                ForthInstruction::Word(word) if word == "[:" => {
+                    quotations.push((Self::default(), location))
+                }
+                ForthInstruction::Word(word) if word == ";]" && nested => {
+                    let (quotation, start) = quotations.pop().unwrap();
+                    quotations.last_mut().unwrap().0.push(
+                        ForthInstruction::Data(StackItem::ExecutionToken(
+                            ExecutionToken::Quotation(quotation),
+                        )),
+                        start,
+                    );
+                }
+                other => current.push(other, location),
+            }
+        }
+        // Unterminated quotations are left as plain words
+        while quotations.len() > 1 {
+            let (mut quotation, start) = quotations.pop().unwrap();
+            let (parent, _) = quotations.last_mut().unwrap();
+            parent.push(ForthInstruction::Word(String::from("[:")), start);
+            parent.instructions.append(&mut quotation.instructions);
+            parent.locations.append(&mut quotation.locations);
+        }
+        quotations.pop().unwrap().0
+    }
+
+    fn push(&mut self, instruction: ForthInstruction, location: SourceLocation) {
+        self.instructions.push(instruction);
+        self.locations.push(location);
     }
 
     fn len(&self) -> usize {
-        self.0.len()
+        self.instructions.len()
     }
 
     fn get(&self, u: usize) -> Option<&ForthInstruction> {
-        self.0.get(u)
+        self.instructions.get(u)
+    }
+
+    fn location(&self, u: usize) -> &SourceLocation {
+        &self.locations[u]
     }
 
-    fn iter(&self) -> core::slice::Iter<'_, ForthInstruction> {
-        self.0.iter()
+    fn iter(&self) -> impl Iterator<Item = (&ForthInstruction, &SourceLocation)> {
+        self.instructions.iter().zip(self.locations.iter())
+    }
+}
+
+/// A user defined word, with the locals it declared
+#[derive(Clone, Debug, Default)]
+struct ForthWord {
+    locals: Vec<String>,
+    arguments: usize,
+    instructions: ForthInstructions,
+}
+impl ForthWord {
+    /// Writes the definition as forth source which defines the same word again
+    fn write_definition(&self, name: &str, out: &mut String) {
+        *out += ": ";
+        *out += name;
+        if !self.locals.is_empty() {
+            *out += " {:";
+            for (i, local) in self.locals.iter().enumerate() {
+                if i == self.arguments {
+                    *out += " |";
+                }
+                *out += " ";
+                *out += local;
+            }
+            *out += " :}";
+        }
+        if self.instructions.len() > 0 {
+            *out += &format!(" {}", self.instructions);
+        }
+        *out += " :\n";
     }
 }
 
@@ -259,6 +619,16 @@ impl TryFrom<StackItem> for String {
         }
     }
 }
+impl TryFrom<StackItem> for ExecutionToken {
+    type Error = StackItem;
+
+    fn try_from(value: StackItem) -> Result<Self, Self::Error> {
+        match value {
+            StackItem::ExecutionToken(xt) => Ok(xt),
+            invalid => Err(invalid),
+        }
+    }
+}
 impl TryFrom<StackItem> for isize {
     type Error = StackItem;
 
@@ -279,6 +649,12 @@ impl Stack {
     pub fn push(&mut self, s: StackItem) {
         self.0.push(s);
     }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
     pub fn try_pop<T: TryFrom<StackItem, Error = StackItem>>(&mut self) -> Option<T> {
         match T::try_from(self.pop()?) {
             Ok(valid) => Some(valid),
@@ -309,15 +685,53 @@ impl Stack {
         }
         None
     }
+
+    /// Pops an execution token with an int below it, leaving the stack untouched on failure
+    pub fn try_pop_count_and_xt(&mut self) -> Option<(isize, ExecutionToken)> {
+        let xt = self.try_pop::<ExecutionToken>()?;
+        match self.try_pop::<isize>() {
+            Some(count) => Some((count, xt)),
+            None => {
+                self.push(StackItem::ExecutionToken(xt));
+                None
+            }
+        }
+    }
+
+    /// Pops an execution token, a count below it and that many items below the count
+    pub fn try_pop_items_and_xt(&mut self) -> Option<(Vec<StackItem>, ExecutionToken)> {
+        let (count, xt) = self.try_pop_count_and_xt()?;
+        if count < 0 || count as usize > self.0.len() {
+            self.push(StackItem::Int(count));
+            self.push(StackItem::ExecutionToken(xt));
+            return None;
+        }
+        let items = self.0.split_off(self.0.len() - count as usize);
+        Some((items, xt))
+    }
 }
 
 pub struct ForthMachine {
     pub instruction_counter: usize,
     pub instructions: ForthInstructions,
     pub stack: Stack,
-    words: BTreeMap<String, ForthInstructions>,
+    words: BTreeMap<String, ForthWord>,
+    locals: Vec<BTreeMap<String, StackItem>>,
+    location: SourceLocation,
+    call_trace: Vec<(String, SourceLocation)>,
     default_words: BTreeMap<&'static str, ForthFunction>,
     pub formatter: UniversalVgaFormatter,
+    aborted: bool,
+    run_depth: usize,
+    steps: usize,
+    instruction_budget: Option<usize>,
+    included_files: BTreeSet<String>,
+    input: Option<String>,
+    /// How many arguments the command line gave, for the next app which starts
+    command_arguments: Option<usize>,
+    /// How many of the running app's arguments from the command line are left
+    app_arguments: usize,
+    variables: BTreeMap<String, String>,
 }
 impl Default for ForthMachine {
     fn default() -> Self {
@@ -335,7 +749,18 @@ impl Default for ForthMachine {
             ("*", &forth_mul),
             ("%", &forth_mod),
             (":", &forth_new_word),
+            ("begin", &forth_marker),
+            ("then", &forth_marker),
             ("again", &forth_again),
+            ("execute", &forth_execute),
+            ("times", &forth_times),
+            ("map", &forth_map),
+            ("filter", &forth_filter),
+            ("budget", &forth_budget),
+            ("evaluate", &forth_evaluate),
+            ("set", &forth_set),
+            ("get", &forth_get),
+            ("env", &forth_env),
         ];
 
         Self {
@@ -344,6 +769,18 @@ impl Default for ForthMachine {
             instructions: ForthInstructions::default(),
             stack: Stack::default(),
             words: BTreeMap::default(),
+            locals: Vec::new(),
+            location: SourceLocation::default(),
+            call_trace: Vec::new(),
+            aborted: false,
+            run_depth: 0,
+            steps: 0,
+            instruction_budget: None,
+            included_files: BTreeSet::new(),
+            input: None,
+            command_arguments: None,
+            app_arguments: 0,
+            variables: BTreeMap::new(),
             default_words: BTreeMap::from_iter(default_words.into_iter().cloned()),
         }
     }
@@ -352,56 +789,317 @@ impl ForthMachine {
     pub fn insert_default_word(&mut self, name: &'static str, f: ForthFunction) {
         self.default_words.insert(name, f);
     }
+    /// The names of every defined word, default and user defined
+    pub fn word_names(&self) -> Vec<String> {
+        self.default_words
+            .keys()
+            .map(|name| String::from(*name))
+            .chain(self.words.keys().cloned())
+            .collect()
+    }
+    /// All user defined words and environment variables as forth source, running it defines them again
+    ///
+    /// STATUS only tells how the last app went, so it isn't included
+    pub fn dictionary_source(&self) -> String {
+        let mut source = String::new();
+        for (name, word) in &self.words {
+            word.write_definition(name, &mut source);
+        }
+        for (name, value) in self.variables.iter().filter(|(name, _)| *name != "STATUS") {
+            source += &format!(
+                "\"{}\" \"{}\" set\n",
+                value.replace('"', "\\\""),
+                name.replace('"', "\\\"")
+            );
+        }
+        source
+    }
     pub fn add_instructions_to_end<S: AsRef<str>>(&mut self, data: &S) {
         self.instructions
-            .add_instructions_to_end(&data.as_ref().chars().collect())
+            .add_instructions_to_end(&data.as_ref().chars().collect(), None)
+    }
+    /// Adds the contents of a script, remembering the file it came from for error reports
+    pub fn add_file_to_end<S: AsRef<str>>(&mut self, data: &S, path: &str) {
+        self.instructions
+            .add_instructions_to_end(&data.as_ref().chars().collect(), Some(Arc::from(path)))
+    }
+    /// Runs the text right away, before the rest of the line or definition which is running
+    ///
+    /// It gets instructions of its own, so it can define words even when called from a word.
+    /// `path` is the file the text came from, if any
+    pub fn run_source<S: AsRef<str>>(&mut self, data: &S, path: Option<&str>) {
+        let source =
+            ForthInstructions::parse(&data.as_ref().chars().collect(), path.map(Arc::from));
+        let instructions = core::mem::replace(&mut self.instructions, source);
+        let instruction_counter = core::mem::replace(&mut self.instruction_counter, 0);
+        self.run_to_end();
+        self.instructions = instructions;
+        // Aborting stops what ran the text as well
+        self.instruction_counter = match self.aborted {
+            true => self.instructions.len(),
+            false => instruction_counter,
+        };
+    }
+    /// Remembers that a file has been included, returns false if it already was
+    pub fn mark_included(&mut self, path: &str) -> bool {
+        self.included_files.insert(String::from(path))
+    }
+    pub fn is_included(&self, path: &str) -> bool {
+        self.included_files.contains(path)
+    }
+    /// Where the instruction which is running was read from
+    pub fn location(&self) -> &SourceLocation {
+        &self.location
     }
     pub fn run(&mut self) {
         if self.instruction_counter >= self.instructions.len() {
             // Dont run because there are no instructions to run
             return;
         }
-        let instruction_to_run = self.instructions.get(self.instruction_counter).unwrap();
+        let instruction_to_run = self
+            .instructions
+            .get(self.instruction_counter)
+            .unwrap()
+            .clone();
+        self.location = self.instructions.location(self.instruction_counter).clone();
+        if !self.step() {
+            return;
+        }
 
         self.instruction_counter += 1;
 
         match instruction_to_run {
             ForthInstruction::Data(si) => {
-                self.stack.push(si.clone());
+                self.stack.push(si);
             }
             ForthInstruction::Word(word) => {
-                // Find word in default_words
-                // Then in new words i guess
-                if let Some(f) = self.default_words.get(word.as_str()) {
-                    (*f)(self);
-                } else if let Some(instructions) = self.words.get(word.as_str()) {
-                    self.run_instructions_locally(instructions.clone());
-                }
+                self.run_word_by_name(&word);
             }
         }
     }
 
+    /// Runs every remaining instruction, or until execution is aborted
     pub fn run_to_end(&mut self) {
+        if self.run_depth == 0 {
+            // A new line starts with a clean slate
+            INTERRUPT_REQUESTED.store(false, Ordering::Relaxed);
+            self.aborted = false;
+            self.steps = 0;
+            INTERRUPTIBLE.store(true, Ordering::Relaxed);
+        }
+        self.run_depth += 1;
         while self.instruction_counter < self.instructions.len() {
             self.run();
         }
+        self.run_depth -= 1;
+        if self.run_depth == 0 {
+            INTERRUPTIBLE.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// Runs an app which reads the keyboard itself, such as an editor
+    ///
+    /// Ctrl+C reaches it as an ordinary key and doesn't abort what runs after it
+    pub fn interactive<T>(&mut self, app: impl FnOnce(&mut Self) -> T) -> T {
+        let interruptible = INTERRUPTIBLE.swap(false, Ordering::Relaxed);
+        let result = app(self);
+        INTERRUPT_REQUESTED.store(false, Ordering::Relaxed);
+        INTERRUPTIBLE.store(interruptible, Ordering::Relaxed);
+        result
+    }
+
+    /// Limits how many instructions may run before the current line is aborted
+    pub fn set_instruction_budget(&mut self, budget: Option<usize>) {
+        self.instruction_budget = budget;
+    }
+
+    /// Checks if Ctrl+C was pressed, aborting execution if it was
+    ///
+    /// Long running apps should call this regularly and stop once it returns true
+    pub fn check_interrupt(&mut self) -> bool {
+        if INTERRUPT_REQUESTED.swap(false, Ordering::Relaxed) {
+            self.abort("interrupted");
+        }
+        self.aborted
+    }
+
+    /// Whether execution was aborted and everything running should stop
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
+    }
+
+    /// Reports an error and stops everything which is running, returning to the prompt
+    pub fn abort(&mut self, message: &str) {
+        self.error(message);
+        self.aborted = true;
+        self.instruction_counter = self.instructions.len();
+    }
+
+    /// Called before every instruction, returns false once execution has to stop
+    fn step(&mut self) -> bool {
+        if self.check_interrupt() {
+            return false;
+        }
+        self.steps += 1;
+        if let Some(budget) = self.instruction_budget {
+            if self.steps > budget {
+                self.abort(&format!("instruction budget of {budget} exhausted"));
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Prints an error together with where it happened and the user words which led there
+    pub fn error(&mut self, message: &str) {
+        let mut report = format!("\nERROR: {message}\n  at {}\n", self.location);
+        for (word, called_at) in self.call_trace.iter().rev() {
+            report += &format!("  in {word}, called at {called_at}\n");
+        }
+        // Errors are shown even when the output is captured
+        let captured = self.formatter.capture_output(None);
+        self.formatter.write_str(&report);
+        self.formatter.capture_output(captured);
+    }
+
+    /// Gives text to the next app which reads input, such as the output of the app before it in a pipeline
+    pub fn set_input(&mut self, input: Option<String>) {
+        self.input = input;
+    }
+    /// Takes the text given to the running app, None when nothing was piped into it
+    pub fn take_input(&mut self) -> Option<String> {
+        self.input.take()
+    }
+
+    /// Tells the next app which starts how many of the strings on the stack are its arguments
+    pub fn set_command_arguments(&mut self, count: Option<usize>) {
+        self.command_arguments = count;
+    }
+    /// Runs an app, giving it the arguments the command line left for it
+    ///
+    /// Apps it starts in turn get none, so they can't take its arguments or anything else
+    pub fn start_app<T>(&mut self, app: impl FnOnce(&mut Self) -> T) -> T {
+        let arguments = self.command_arguments.take().unwrap_or(0);
+        let outer = core::mem::replace(&mut self.app_arguments, arguments);
+        let result = app(self);
+        self.app_arguments = outer;
+        result
+    }
+    /// Pops an argument the command line gave the running app, None once there are no more
+    ///
+    /// Optional arguments are taken this way, so strings which were already on the stack stay
+    pub fn pop_argument(&mut self) -> Option<String> {
+        if self.app_arguments == 0 {
+            return None;
+        }
+        let argument = self.stack.try_pop::<String>()?;
+        self.app_arguments -= 1;
+        Some(argument)
+    }
+
+    /// Environment variables belong to the session, such as PATH
+    pub fn set_variable(&mut self, name: &str, value: &str) {
+        self.variables
+            .insert(String::from(name), String::from(value));
+    }
+    pub fn variable(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(String::as_str)
+    }
+
+    fn run_word_by_name(&mut self, word: &str) {
+        // Locals of the running definition shadow every other word
+        // Then default_words, then new words i guess
+        if let Some(value) = self.locals.last().and_then(|frame| frame.get(word)) {
+            self.stack.push(value.clone());
+        } else if let Some(f) = self.default_word(word) {
+            f(self);
+        } else if let Some(user_word) = self.words.get(word) {
+            if self.call_trace.len() >= MAX_CALL_DEPTH {
+                self.abort("return stack overflow");
+                return;
+            }
+            let user_word = user_word.clone();
+            self.call_trace
+                .push((String::from(word), self.location.clone()));
+            self.run_word(user_word);
+            self.call_trace.pop();
+        } else {
+            self.error(&format!("unknown word {word}"));
+        }
+    }
+
+    /// Control flow words are found in any case, just like AGAIN and IF find BEGIN and THEN
+    fn default_word(&self, word: &str) -> Option<ForthFunction> {
+        if let Some(f) = self.default_words.get(word) {
+            return Some(*f);
+        }
+        match word.to_ascii_lowercase().as_str() {
+            marker @ ("begin" | "then" | "again") => self.default_words.get(marker).copied(),
+            _ => None,
+        }
+    }
+
+    /// Runs a word or quotation passed around as an execution token
+    pub fn execute(&mut self, xt: ExecutionToken) {
+        match xt {
+            ExecutionToken::Word(word) => self.run_word_by_name(&word),
+            ExecutionToken::Quotation(instructions) => self.run_instructions_locally(instructions),
+        }
+    }
+
+    /// Every call gets its own frame, even without locals, so callees never see the caller's locals
+    fn run_word(&mut self, word: ForthWord) {
+        if self.stack.0.len() < word.arguments {
+            self.error("not enough items on the stack for locals");
+            return;
+        }
+        let arguments = self.stack.0.split_off(self.stack.0.len() - word.arguments);
+        let mut frame = BTreeMap::new();
+        for (name, value) in word.locals.iter().zip(arguments) {
+            frame.insert(name.clone(), value);
+        }
+        for name in &word.locals[word.arguments..] {
+            frame.insert(name.clone(), StackItem::Int(0));
+        }
+        self.locals.push(frame);
+        self.run_instructions_locally(word.instructions);
+        self.locals.pop();
     }
 
     fn run_instructions_locally(&mut self, fi: ForthInstructions) {
-        for fi in fi.iter() {
+        let mut instructions = fi.iter();
+        while let Some((fi, location)) = instructions.next() {
+            self.location = location.clone();
+            if !self.step() {
+                break;
+            }
             match fi {
                 ForthInstruction::Data(si) => {
                     self.stack.push(si.clone());
                 }
-                ForthInstruction::Word(word) => {
-                    // Find word in default_words
-                    // Then in new words i guess
-                    if let Some(f) = self.default_words.get(word.as_str()) {
-                        (*f)(self);
-                    } else if let Some(instructions) = self.words.get(word.as_str()) {
-                        self.run_instructions_locally((*instructions).clone());
+                ForthInstruction::Word(word) if word.eq_ignore_ascii_case("to") => {
+                    match instructions.next() {
+                        Some((ForthInstruction::Word(name), _)) => self.assign_local(name),
+                        _ => self.error("TO expects the name of a local"),
                     }
                 }
+                ForthInstruction::Word(word) => {
+                    self.run_word_by_name(word);
+                }
+            }
+        }
+    }
+
+    fn assign_local(&mut self, name: &str) {
+        let Some(value) = self.stack.pop() else {
+            self.error("TO needs a value on the stack");
+            return;
+        };
+        match self.locals.last_mut().and_then(|frame| frame.get_mut(name)) {
+            Some(local) => *local = value,
+            None => {
+                self.stack.push(value);
+                self.error(&format!("{name} is not a local"));
             }
         }
     }
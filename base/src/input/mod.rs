@@ -8,6 +8,12 @@ pub const ALT_MODIFIER: usize = 0b0010_0000_0000;
 pub const ALTGR_MODIFIER: usize = 0b0001_0000_0000;
 
 pub static mut KEYBOARD_QUEUE: Keyboard<KeyEvent> = Keyboard::new();
+/// F1..F4, which together with Alt switch virtual console
+pub const CONSOLE_KEYS: [usize; 4] = [0x3B, 0x3C, 0x3D, 0x3E];
+/// Switches to the virtual console with this index, set by the kernel once consoles exist
+///
+/// Consoles are switched while the active one waits for a key in [Keyboard::get_blocking]
+pub static mut CONSOLE_SWITCHER: Option<fn(usize)> = None;
 pub static mut KEYMAP: [char; 4096] = ['\x00'; 4096];
 
 #[derive(Copy, Clone, PartialEq)]
@@ -114,10 +120,24 @@ pub struct Keyboard<T> {
 impl Keyboard<KeyEvent> {
     pub fn getch_blocking(&mut self) -> char {
         loop {
-            let Some(KeyEvent::KeyPressed { key, modifiers}) = self.queue.dequeue() else {continue};
+            let KeyEvent::KeyPressed { key, modifiers} = self.get_blocking() else {continue};
             return key.resolve_text_char(modifiers).unwrap_or('\0');
         }
     }
+    /// Waits for the next key event, Alt+F1..F4 switch console instead of being returned
+    pub fn get_blocking(&mut self) -> KeyEvent {
+        loop {
+            let Some(event) = self.queue.dequeue() else {continue};
+            if let (KeyEvent::KeyPressed { key, modifiers }, Some(switch)) = (&event, unsafe { CONSOLE_SWITCHER }) {
+                let console = CONSOLE_KEYS.iter().position(|console_key| *console_key == key.0);
+                if let (true, Some(console)) = (modifiers.is_alt_pressed(), console) {
+                    switch(console);
+                    continue;
+                }
+            }
+            return event;
+        }
+    }
     pub fn try_getch(&mut self) -> Option<(ScanCode, Modifiers)> {
         self.queue.dequeue().map(|v| if let KeyEvent::KeyPressed { key, modifiers } = v { Some((key, modifiers))} else {None}).flatten()
     }
@@ -129,13 +149,6 @@ impl<T> Keyboard<T> {
     pub fn get(&mut self) -> Option<T> {
         self.queue.dequeue()
     }
-    pub fn get_blocking(&mut self) -> T {
-        loop {
-            if let Some(valid) = self.queue.dequeue() {
-                return valid;
-            }
-        }
-    }
     pub const fn new() -> Self {
         Self {
             queue: Queue::new(),
@@ -5,6 +5,7 @@ pub mod app;
 pub mod display;
 pub mod forth;
 pub mod input;
+pub mod line_editor;
 pub mod pic;
 pub use app::*;
 pub use display::macros::*;
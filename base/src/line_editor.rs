@@ -0,0 +1,453 @@
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{
+    display::{DefaultVgaWriter, VgaColorCombo},
+    input::{KeyEvent, Modifiers, ScanCode, KEYBOARD_QUEUE},
+};
+
+const KEY_TAB: usize = 0x0F;
+const KEY_R: usize = 0x13;
+const KEY_C: usize = 0x2E;
+const KEY_HOME: usize = 0xE047;
+const KEY_UP: usize = 0xE048;
+const KEY_PAGE_UP: usize = 0xE049;
+const KEY_LEFT: usize = 0xE04B;
+const KEY_RIGHT: usize = 0xE04D;
+const KEY_END: usize = 0xE04F;
+const KEY_DOWN: usize = 0xE050;
+const KEY_PAGE_DOWN: usize = 0xE051;
+const KEY_DELETE: usize = 0xE053;
+
+/// How many lines Shift+PgUp and Shift+PgDn scroll the screen
+const SCROLL_STEP: usize = 12;
+
+/// How many submitted lines the history keeps
+pub const HISTORY_LIMIT: usize = 256;
+
+/// Suggests what the word in front of the cursor could be completed to when tab is pressed
+pub trait Completer {
+    /// Every candidate which could replace `word`, `in_string` is true inside a string literal
+    fn complete(&self, word: &str, in_string: bool) -> Vec<String>;
+}
+/// Completes nothing
+impl Completer for () {
+    fn complete(&self, _word: &str, _in_string: bool) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Text shown in front of the line, parts of it may have colors of their own
+#[derive(Default, Clone)]
+pub struct Prompt {
+    parts: Vec<(String, Option<VgaColorCombo>)>,
+}
+
+impl Prompt {
+    /// Adds text after the prompt, None uses the colors of the writer
+    pub fn push(&mut self, text: &str, color: Option<VgaColorCombo>) {
+        match self.parts.last_mut() {
+            Some((last, last_color)) if *last_color == color => *last += text,
+            _ => self.parts.push((String::from(text), color)),
+        }
+    }
+
+    fn write(&self, writer: &mut DefaultVgaWriter) {
+        let default = writer.get_default_colors();
+        for (text, color) in &self.parts {
+            writer.set_default_colors(color.unwrap_or(default));
+            writer.write_str(text);
+        }
+        writer.set_default_colors(default);
+    }
+}
+
+impl From<&str> for Prompt {
+    fn from(text: &str) -> Self {
+        let mut prompt = Self::default();
+        prompt.push(text, None);
+        prompt
+    }
+}
+
+/// A line of text which the user can edit, drawn in place on the text screen
+///
+/// The shell prompt uses this, but any app reading a line from the user may too
+#[derive(Default)]
+pub struct LineEditor {
+    line: Vec<char>,
+    cursor: usize,
+    prompt: Prompt,
+    start: (usize, usize),
+    drawn: usize,
+    history: Vec<String>,
+    /// The history entry shown while browsing with up and down
+    browsing: Option<usize>,
+    /// The line which was being written before browsing or searching the history
+    draft: Vec<char>,
+    /// The query and matching history entry during a Ctrl+R search
+    search: Option<(String, Option<usize>)>,
+}
+
+impl LineEditor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the prompt and reads keys until enter is pressed
+    pub fn read_line(
+        &mut self,
+        writer: &mut DefaultVgaWriter,
+        prompt: impl Into<Prompt>,
+        completer: &dyn Completer,
+    ) -> String {
+        self.start(writer, prompt);
+        loop {
+            let event = unsafe { KEYBOARD_QUEUE.get_blocking() };
+            if let KeyEvent::KeyPressed { modifiers, key } = event {
+                if let Some(line) = self.handle_key(key, modifiers, writer, completer) {
+                    return line;
+                }
+            }
+        }
+    }
+
+    /// Writes the prompt and starts editing a new empty line after it
+    pub fn start(&mut self, writer: &mut DefaultVgaWriter, prompt: impl Into<Prompt>) {
+        self.line.clear();
+        self.cursor = 0;
+        self.drawn = 0;
+        self.browsing = None;
+        self.search = None;
+        self.prompt = prompt.into();
+        self.prompt.write(writer);
+        self.start = writer.get_position();
+    }
+
+    /// Previously submitted lines, oldest first
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Adds lines to the history, for example ones saved by an earlier session
+    pub fn load_history<I: IntoIterator<Item = String>>(&mut self, lines: I) {
+        for line in lines {
+            self.push_history(line);
+        }
+    }
+
+    fn push_history(&mut self, line: String) {
+        if line.trim().is_empty() || self.history.last() == Some(&line) {
+            return;
+        }
+        self.history.push(line);
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+    }
+
+    /// Handles a key press, returning the finished line once enter is pressed
+    pub fn handle_key(
+        &mut self,
+        key: ScanCode,
+        modifiers: Modifiers,
+        writer: &mut DefaultVgaWriter,
+        completer: &dyn Completer,
+    ) -> Option<String> {
+        // Looking at the scrollback doesn't touch the line, which would go back to live output
+        match (key.0, modifiers.is_shift_pressed()) {
+            (KEY_PAGE_UP, true) => {
+                writer.scroll_view_up(SCROLL_STEP);
+                return None;
+            }
+            (KEY_PAGE_DOWN, true) => {
+                writer.scroll_view_down(SCROLL_STEP);
+                return None;
+            }
+            _ => (),
+        }
+        if let Some((query, found)) = self.search.take() {
+            return self.handle_search_key(key, modifiers, query, found, writer, completer);
+        }
+        match (key.0, modifiers.is_ctrl_pressed()) {
+            (KEY_TAB, false) => self.complete(completer, writer),
+            (KEY_UP, _) => self.browse_older(),
+            (KEY_DOWN, _) => self.browse_newer(),
+            (KEY_R, true) => {
+                self.draft = self.line.clone();
+                self.search = Some((String::new(), None));
+            }
+            (KEY_LEFT, false) => self.cursor = self.cursor.saturating_sub(1),
+            (KEY_LEFT, true) => self.cursor = self.previous_word(),
+            (KEY_RIGHT, false) => self.cursor = (self.cursor + 1).min(self.line.len()),
+            (KEY_RIGHT, true) => self.cursor = self.next_word(),
+            (KEY_HOME, _) => self.cursor = 0,
+            (KEY_END, _) => self.cursor = self.line.len(),
+            (KEY_DELETE, _) => {
+                if self.cursor < self.line.len() {
+                    self.line.remove(self.cursor);
+                }
+            }
+            (KEY_C, true) => {
+                // Ctrl+C throws the line away
+                self.cursor = self.line.len();
+                self.redraw(writer);
+                writer.write_str("^C").next_line();
+                self.line.clear();
+                return Some(String::new());
+            }
+            (_, true) => (),
+            _ => match key.resolve_text_char(modifiers) {
+                Some('\n') => return Some(self.submit(writer)),
+                Some('\x08') => {
+                    if self.cursor > 0 {
+                        self.cursor -= 1;
+                        self.line.remove(self.cursor);
+                    }
+                }
+                Some('\x1B') | None => (),
+                Some(char) => {
+                    self.line.insert(self.cursor, char);
+                    self.cursor += 1;
+                }
+            },
+        }
+        self.redraw(writer);
+        None
+    }
+
+    /// Keys typed during a Ctrl+R search edit the query instead of the line
+    fn handle_search_key(
+        &mut self,
+        key: ScanCode,
+        modifiers: Modifiers,
+        mut query: String,
+        mut found: Option<usize>,
+        writer: &mut DefaultVgaWriter,
+        completer: &dyn Completer,
+    ) -> Option<String> {
+        match (key.0, modifiers.is_ctrl_pressed()) {
+            (KEY_R, true) => {
+                let before = found.unwrap_or(self.history.len());
+                found = self.find_in_history(&query, before).or(found);
+            }
+            (KEY_C, true) => {
+                self.set_line(self.draft.clone());
+                self.redraw(writer);
+                return None;
+            }
+            (_, true) => (),
+            _ => match key.resolve_text_char(modifiers) {
+                Some('\n') => return Some(self.submit(writer)),
+                Some('\x1B') => {
+                    self.set_line(self.draft.clone());
+                    self.redraw(writer);
+                    return None;
+                }
+                Some('\x08') => {
+                    query.pop();
+                    found = self.find_in_history(&query, self.history.len());
+                }
+                Some(char) => {
+                    query.push(char);
+                    found = self.find_in_history(&query, self.history.len());
+                }
+                // Any other key ends the search, keeping the match, and is handled as usual
+                None => return self.handle_key(key, modifiers, writer, completer),
+            },
+        }
+        if let Some(index) = found {
+            self.set_line(self.history[index].chars().collect());
+        }
+        self.search = Some((query, found));
+        self.redraw(writer);
+        None
+    }
+
+    /// The newest history entry before `before` which contains the query
+    fn find_in_history(&self, query: &str, before: usize) -> Option<usize> {
+        if query.is_empty() {
+            return None;
+        }
+        self.history[..before]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+
+    fn browse_older(&mut self) {
+        let index = match self.browsing {
+            _ if self.history.is_empty() => return,
+            None => {
+                self.draft = self.line.clone();
+                self.history.len() - 1
+            }
+            Some(index) => index.saturating_sub(1),
+        };
+        self.browsing = Some(index);
+        self.set_line(self.history[index].chars().collect());
+    }
+
+    fn browse_newer(&mut self) {
+        match self.browsing {
+            None => (),
+            Some(index) if index + 1 < self.history.len() => {
+                self.browsing = Some(index + 1);
+                self.set_line(self.history[index + 1].chars().collect());
+            }
+            Some(_) => {
+                self.browsing = None;
+                self.set_line(self.draft.clone());
+            }
+        }
+    }
+
+    /// Completes the word in front of the cursor, listing the candidates if it's ambiguous
+    fn complete(&mut self, completer: &dyn Completer, writer: &mut DefaultVgaWriter) {
+        let before_cursor = &self.line[..self.cursor];
+        let quotes = before_cursor
+            .iter()
+            .enumerate()
+            .filter(|(i, char)| **char == '"' && (*i == 0 || before_cursor[i - 1] != '\\'))
+            .count();
+        let in_string = quotes % 2 == 1;
+        let word_start = before_cursor
+            .iter()
+            .rposition(|char| match in_string {
+                true => *char == '"',
+                false => char.is_whitespace(),
+            })
+            .map_or(0, |i| i + 1);
+        let word: String = before_cursor[word_start..].iter().collect();
+
+        let candidates = completer.complete(&word, in_string);
+        let completed: Vec<char> = match candidates.as_slice() {
+            [] => return,
+            [only] => {
+                let mut completed: Vec<char> = only.chars().collect();
+                // Finish the word unless it's a directory which may be continued
+                if self.cursor == self.line.len() && !only.ends_with('/') {
+                    if in_string {
+                        completed.push('"');
+                    }
+                    completed.push(' ');
+                }
+                completed
+            }
+            [first, rest @ ..] => {
+                let mut common: Vec<char> = first.chars().collect();
+                for candidate in rest {
+                    let length = common
+                        .iter()
+                        .zip(candidate.chars())
+                        .take_while(|(a, b)| **a == *b)
+                        .count();
+                    common.truncate(length);
+                }
+                if common.len() <= word.chars().count() {
+                    self.list_candidates(&candidates, writer);
+                    return;
+                }
+                common
+            }
+        };
+        let length = completed.len();
+        self.line.splice(word_start..self.cursor, completed);
+        self.cursor = word_start + length;
+    }
+
+    /// Writes the candidates below the line and starts the line over after them
+    fn list_candidates(&mut self, candidates: &[String], writer: &mut DefaultVgaWriter) {
+        let cursor = self.cursor;
+        self.cursor = self.line.len();
+        self.redraw(writer);
+        writer.next_line();
+        writer.write_str(candidates.join("  ")).next_line();
+        self.prompt.write(writer);
+        self.start = writer.get_position();
+        self.drawn = 0;
+        self.cursor = cursor;
+    }
+
+    fn set_line(&mut self, line: Vec<char>) {
+        self.line = line;
+        self.cursor = self.line.len();
+    }
+
+    fn submit(&mut self, writer: &mut DefaultVgaWriter) -> String {
+        self.cursor = self.line.len();
+        self.redraw(writer);
+        writer.next_line();
+        let line: String = self.line.iter().collect();
+        self.push_history(line.clone());
+        self.line.clear();
+        self.cursor = 0;
+        self.drawn = 0;
+        self.browsing = None;
+        line
+    }
+
+    /// Shown in front of the line while searching the history
+    fn search_prompt(&self) -> String {
+        match &self.search {
+            Some((query, None)) if !query.is_empty() => {
+                format!("(failed reverse-i-search)`{query}': ")
+            }
+            Some((query, _)) => format!("(reverse-i-search)`{query}': "),
+            None => String::new(),
+        }
+    }
+
+    fn previous_word(&self) -> usize {
+        let mut cursor = self.cursor;
+        while cursor > 0 && self.line[cursor - 1].is_whitespace() {
+            cursor -= 1;
+        }
+        while cursor > 0 && !self.line[cursor - 1].is_whitespace() {
+            cursor -= 1;
+        }
+        cursor
+    }
+
+    fn next_word(&self) -> usize {
+        let mut cursor = self.cursor;
+        while cursor < self.line.len() && self.line[cursor].is_whitespace() {
+            cursor += 1;
+        }
+        while cursor < self.line.len() && !self.line[cursor].is_whitespace() {
+            cursor += 1;
+        }
+        cursor
+    }
+
+    /// Draws the whole line from its start, it may span several rows of the screen
+    fn redraw(&mut self, writer: &mut DefaultVgaWriter) {
+        let (width, height) = writer.get_size();
+        let prompt = self.search_prompt();
+        let prompt_len = prompt.chars().count();
+        let text: String = self.line.iter().collect();
+        let padding = self.drawn.saturating_sub(prompt_len + self.line.len());
+        writer
+            .set_position(self.start)
+            .write_str(&prompt)
+            .write_str(&text)
+            .write_str(" ".repeat(padding));
+        self.drawn = prompt_len + self.line.len();
+
+        // Reaching the bottom of the screen scrolls everything up, the start of the line included
+        let written = self.drawn + padding;
+        let expected = self.start.1 * width + self.start.0 + written;
+        let (x, y) = writer.get_position();
+        let scrolled = expected.saturating_sub(y * width + x) / width;
+        self.start.1 = self.start.1.saturating_sub(scrolled);
+
+        let offset = self.start.0 + prompt_len + self.cursor;
+        let (cursor_x, mut cursor_y) = (offset % width, self.start.1 + offset / width);
+        if cursor_y >= height {
+            writer.prepare_print();
+            self.start.1 = self.start.1.saturating_sub(1);
+            cursor_y -= 1;
+        }
+        writer
+            .set_position((cursor_x, cursor_y))
+            .update_cursor(cursor_x as u8, cursor_y as u8);
+    }
+}
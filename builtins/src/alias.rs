@@ -0,0 +1,87 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use base::forth::ForthMachine;
+use fs::PathString;
+
+use crate::{read_source, write_data_file};
+
+/// Aliases are kept here, one per line as the name followed by what it stands for
+pub const ALIASES_PATH: &str = "/aliases.txt";
+
+/// Every alias by name, read from the aliases file
+pub fn load_aliases() -> BTreeMap<String, String> {
+    let Ok(text) = read_source(&PathString::from(ALIASES_PATH)) else {
+        return BTreeMap::new();
+    };
+    text.lines()
+        .filter_map(|line| line.trim().split_once(' '))
+        .map(|(name, expansion)| (name.to_string(), expansion.trim().to_string()))
+        .collect()
+}
+
+fn save_aliases(aliases: &BTreeMap<String, String>) {
+    let mut text = String::new();
+    for (name, expansion) in aliases {
+        text += &format!("{name} {expansion}\n");
+    }
+    // Aliases which can't be saved still work until the system restarts
+    let _ = write_data_file(PathString::from(ALIASES_PATH), text.into_bytes());
+}
+
+/// Replaces an alias at the start of the line with what it stands for, aliases aren't expanded again
+pub fn expand_alias(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let (name, rest) = trimmed
+        .split_once(char::is_whitespace)
+        .unwrap_or((trimmed, ""));
+    match load_aliases().remove(name) {
+        Some(expansion) => format!("{expansion} {rest}"),
+        None => String::from(line),
+    }
+}
+
+// "expansion" "name" ALIAS, typing name at the start of a line runs expansion
+pub(crate) fn forth_alias(machine: &mut ForthMachine) {
+    let Some(name) = machine.stack.try_pop::<String>() else {
+        machine.error("ALIAS expects what the alias stands for and a name");
+        return;
+    };
+    let Some(expansion) = machine.stack.try_pop::<String>() else {
+        machine.error("ALIAS expects what the alias stands for and a name");
+        return;
+    };
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        machine.error("ALIAS names can't be empty or contain spaces");
+        return;
+    }
+    let mut aliases = load_aliases();
+    aliases.insert(name, expansion);
+    save_aliases(&aliases);
+}
+
+// "name" UNALIAS
+pub(crate) fn forth_unalias(machine: &mut ForthMachine) {
+    let Some(name) = machine.stack.try_pop::<String>() else {
+        machine.error("UNALIAS expects a name");
+        return;
+    };
+    let mut aliases = load_aliases();
+    if aliases.remove(&name).is_none() {
+        machine.error(&format!("UNALIAS: no alias named {name}"));
+        return;
+    }
+    save_aliases(&aliases);
+}
+
+// ALIASES, lists every alias
+pub(crate) fn forth_aliases(machine: &mut ForthMachine) {
+    let list: Vec<String> = load_aliases()
+        .into_iter()
+        .map(|(name, expansion)| format!("\n{name} = {expansion}"))
+        .collect();
+    machine.formatter.write_str(list.concat());
+}
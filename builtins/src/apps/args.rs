@@ -0,0 +1,48 @@
+use alloc::string::String;
+use base::{
+    forth::{ForthMachine, StackItem},
+    ProgramError,
+};
+use fs::{FileSystemError, PathString};
+
+use crate::resolve;
+
+/// Pops a path argument, relative paths are in the active directory
+pub(crate) fn pop_path(machine: &mut ForthMachine) -> Result<PathString, ProgramError> {
+    machine
+        .stack
+        .try_pop::<String>()
+        .map(|path| resolve(&path))
+        .ok_or(ProgramError::InvalidStartParameter)
+}
+
+/// Pops the argument if it's the flag, like `-r`, leaving anything else on the stack
+pub(crate) fn pop_flag(machine: &mut ForthMachine, flag: &str) -> bool {
+    match machine.stack.try_pop::<String>() {
+        Some(argument) if argument == flag => true,
+        Some(argument) => {
+            machine.stack.push(StackItem::String(argument));
+            false
+        }
+        None => false,
+    }
+}
+
+/// Describes why a file operation failed
+pub(crate) fn file_error(error: FileSystemError) -> ProgramError {
+    ProgramError::Custom(match error {
+        FileSystemError::FileNotFound(_) | FileSystemError::DirectoryNotFound => {
+            "no such file or directory"
+        }
+        FileSystemError::IncorrectFileType(description) => description,
+        FileSystemError::NameAlreadyExists => "a file with that name already exists",
+        FileSystemError::DirectoryNotEmpty => "the directory isn't empty, use -r",
+        FileSystemError::ReadOnly => "the file is read-only",
+        FileSystemError::InvalidName => "names can't be empty, . or .. or contain / * or ?",
+        FileSystemError::InvalidParentDirectory => "invalid parent directory",
+        FileSystemError::Busy => "the file is in use",
+        FileSystemError::EmptyPath => "the path is empty",
+        FileSystemError::Interrupted => "interrupted",
+        _ => "the file system failed",
+    })
+}
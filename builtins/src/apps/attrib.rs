@@ -0,0 +1,88 @@
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use base::{
+    forth::{ForthMachine, StackItem},
+    LittleManApp, ProgramError,
+};
+use fs::{AppConstructor, Attributes, DefaultInstall, PathString};
+
+use super::args::{file_error, pop_path};
+
+/// Shows or changes the attributes of a file
+///
+/// `attrib notes.txt` shows them, `attrib +r notes.txt` makes the file read-only and
+/// `attrib -h notes.txt` lists it in dir again. Apps are executable, which can't be changed
+#[derive(Default)]
+pub struct Attrib;
+pub struct AttribApp;
+
+impl DefaultInstall for Attrib {
+    fn path() -> PathString {
+        PathString::from("attrib.run")
+    }
+}
+impl AppConstructor for Attrib {
+    fn instantiate(&self) -> Box<dyn LittleManApp> {
+        Box::new(AttribApp)
+    }
+}
+impl LittleManApp for AttribApp {
+    fn run(&mut self, handle: &mut ForthMachine) -> Result<(), ProgramError> {
+        let mut changes = Vec::new();
+        while let Some(change) = pop_change(handle) {
+            changes.push(change);
+        }
+        let path = pop_path(handle)?;
+        let attributes = match changes.is_empty() {
+            true => fs::stat(&path).map_err(file_error)?.attributes,
+            false => {
+                let mut file = fs::get_file_write(&path).map_err(file_error)?;
+                let mut attributes = file.stat().attributes;
+                for (set, attribute) in changes {
+                    attributes = match set {
+                        true => attributes | attribute,
+                        false => attributes.without(attribute),
+                    };
+                }
+                file.set_attributes(attributes);
+                attributes
+            }
+        };
+        handle.formatter.next_line().write_str(&format!(
+            "{}  {}",
+            describe(attributes),
+            path.as_str()
+        ));
+        Ok(())
+    }
+}
+
+/// Pops an argument like `+r` or `-h`, whether it's set and the attribute, leaving anything else
+fn pop_change(handle: &mut ForthMachine) -> Option<(bool, Attributes)> {
+    let argument = handle.stack.try_pop::<String>()?;
+    let change = match argument.as_str() {
+        "+r" => Some((true, Attributes::READ_ONLY)),
+        "-r" => Some((false, Attributes::READ_ONLY)),
+        "+h" => Some((true, Attributes::HIDDEN)),
+        "-h" => Some((false, Attributes::HIDDEN)),
+        _ => None,
+    };
+    if change.is_none() {
+        handle.stack.push(StackItem::String(argument));
+    }
+    change
+}
+
+/// Like `rh-`, r is read-only, h hidden and x executable
+fn describe(attributes: Attributes) -> String {
+    [
+        (Attributes::READ_ONLY, 'r'),
+        (Attributes::HIDDEN, 'h'),
+        (Attributes::EXECUTABLE, 'x'),
+    ]
+    .into_iter()
+    .map(|(attribute, letter)| match attributes.contains(attribute) {
+        true => letter,
+        false => '-',
+    })
+    .collect()
+}
@@ -0,0 +1,36 @@
+use alloc::{boxed::Box, string::String};
+use base::{forth::ForthMachine, LittleManApp, ProgramError};
+use fs::{AppConstructor, DefaultInstall, PathString};
+
+use super::args::{file_error, pop_path};
+
+/// Prints a file, `cat notes.txt`
+#[derive(Default)]
+pub struct PrintFile;
+pub struct PrintFileApp;
+
+impl DefaultInstall for PrintFile {
+    fn path() -> PathString {
+        PathString::from("cat.run")
+    }
+}
+impl AppConstructor for PrintFile {
+    fn instantiate(&self) -> Box<dyn LittleManApp> {
+        Box::new(PrintFileApp)
+    }
+}
+impl LittleManApp for PrintFileApp {
+    fn run(&mut self, handle: &mut ForthMachine) -> Result<(), ProgramError> {
+        let path = pop_path(handle)?;
+        let data = fs::get_file(&path)
+            .and_then(|file| file.read_file())
+            .map_err(file_error)?;
+        for line in String::from_utf8_lossy(&data).lines() {
+            if handle.check_interrupt() {
+                break;
+            }
+            handle.formatter.next_line().write_str(line);
+        }
+        Ok(())
+    }
+}
@@ -29,7 +29,7 @@ impl LittleManApp for ChangeDirApp {
         let path = {
             match machine.stack.pop() {
                 Some(StackItem::String(string)) => {
-                    Ok(fs::active_directory().append(&string).clean())
+                    Ok(crate::resolve(&string))
                 }
                 Some(invalid) => {
                     machine.stack.push(invalid);
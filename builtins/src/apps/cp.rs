@@ -0,0 +1,41 @@
+use alloc::boxed::Box;
+use base::{forth::ForthMachine, LittleManApp, ProgramError};
+use fs::{AppConstructor, DefaultInstall, FileSystemError, PathString};
+
+use super::args::{file_error, pop_flag, pop_path};
+
+/// Copies a file, `cp notes.txt backup.txt`, directories are copied with `cp -r`
+#[derive(Default)]
+pub struct CopyFile;
+pub struct CopyFileApp;
+
+impl DefaultInstall for CopyFile {
+    fn path() -> PathString {
+        PathString::from("cp.run")
+    }
+}
+impl AppConstructor for CopyFile {
+    fn instantiate(&self) -> Box<dyn LittleManApp> {
+        Box::new(CopyFileApp)
+    }
+}
+impl LittleManApp for CopyFileApp {
+    fn run(&mut self, handle: &mut ForthMachine) -> Result<(), ProgramError> {
+        let recursive = pop_flag(handle, "-r");
+        let from = pop_path(handle)?;
+        let mut to = pop_path(handle)?;
+        if fs::get_file(&from).map_err(file_error)?.is_directory() && !recursive {
+            return Err(ProgramError::Custom("copying a directory needs -r"));
+        }
+        // Copying into a directory keeps the name
+        if fs::get_file(&to).is_ok_and(|file| file.is_directory()) {
+            let name = from.clone().pop().ok_or(ProgramError::InvalidParameter)?;
+            to = to.append(&name);
+        }
+        match fs::copy_until(from, to, &mut || handle.check_interrupt()) {
+            // Ctrl+C already aborted and said so
+            Err(FileSystemError::Interrupted) => Ok(()),
+            result => result.map_err(file_error),
+        }
+    }
+}
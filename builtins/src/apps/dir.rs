@@ -1,7 +1,12 @@
 use alloc::{boxed::Box, format, vec::Vec};
 use base::{forth::ForthMachine, LittleManApp, OsHandle, ProgramError};
-use fs::{AppConstructor, DefaultInstall, FileMetadata, PathString};
+use fs::{AppConstructor, Attributes, DefaultInstall, FileMetadata, PathString};
 
+use crate::prompt::format_time;
+
+/// Lists the active directory with sizes and when files last changed, `dir -a` lists hidden files too
+///
+/// `dir *.for` or `dir /bin/**/*.run` lists the files matching a pattern instead
 #[derive(Default)]
 pub struct Dir;
 pub struct DirApp;
@@ -25,17 +30,32 @@ impl LittleManApp for DirApp {
         const RUNNER: &[u8] = &[0xC2, 0xC3];
         const FILE: &[u8] = &[0xC4, 0xC5];
 
+        let mut all = false;
+        let mut pattern = None;
+        while let Some(argument) = handle.pop_argument() {
+            match argument.as_str() {
+                "-a" => all = true,
+                _ => pattern = Some(argument),
+            }
+        }
         let path = fs::active_directory();
         handle
             .formatter
             .next_line()
             .write_str("Listing ")
-            .write_str(path.as_str())
+            .write_str(pattern.as_deref().unwrap_or(path.as_str()))
             .next_line();
 
-        match fs::read_dir(path) {
-            Ok(dirs) => {
-                let vec: Vec<_> = dirs.items().collect();
+        let items = match &pattern {
+            Some(pattern) => Ok(fs::glob(pattern).collect::<Vec<_>>()),
+            None => fs::read_dir(path).map(|dirs| dirs.items().collect()),
+        };
+        match items {
+            Ok(items) => {
+                let vec: Vec<_> = items
+                    .into_iter()
+                    .filter(|item| all || !item.attributes.contains(Attributes::HIDDEN))
+                    .collect();
                 for (i, item) in vec.iter().enumerate() {
                     handle.formatter.write_str("  ");
                     if i == vec.len() - 1 {
@@ -43,7 +63,13 @@ impl LittleManApp for DirApp {
                     } else {
                         handle.formatter.write_raw_char(BRACKET);
                     };
-                    let FileMetadata { path, filetype } = item;
+                    let FileMetadata {
+                        path,
+                        filetype,
+                        size,
+                        modified,
+                        ..
+                    } = item;
                     let icon = match filetype {
                         fs::FileType::Directory => FOLDER,
                         fs::FileType::Data => FILE,
@@ -55,7 +81,12 @@ impl LittleManApp for DirApp {
                         .write_raw_char(icon[0])
                         .write_raw_char(icon[1])
                         .write_str("] ")
-                        .write_str(path.as_str())
+                        .write_str(&format!(
+                            "{:<24}{:>8} {}",
+                            path.as_str(),
+                            size,
+                            format_time(*modified)
+                        ))
                         .next_line();
                 }
             }
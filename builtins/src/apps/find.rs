@@ -0,0 +1,38 @@
+use alloc::{boxed::Box, format, string::String};
+use base::{forth::ForthMachine, LittleManApp, ProgramError};
+use fs::{AppConstructor, DefaultInstall, PathString};
+
+/// Prints the paths of the files matching a pattern in or below the active directory
+///
+/// `find *.for` looks at names in every directory below, `find bin/**/*.run` matches whole paths
+/// and `find` alone prints everything
+#[derive(Default)]
+pub struct Find;
+pub struct FindApp;
+
+impl DefaultInstall for Find {
+    fn path() -> PathString {
+        PathString::from("find.run")
+    }
+}
+impl AppConstructor for Find {
+    fn instantiate(&self) -> Box<dyn LittleManApp> {
+        Box::new(FindApp)
+    }
+}
+impl LittleManApp for FindApp {
+    fn run(&mut self, handle: &mut ForthMachine) -> Result<(), ProgramError> {
+        let pattern = handle.pop_argument().unwrap_or(String::from("*"));
+        let pattern = match pattern.contains('/') {
+            true => pattern,
+            false => format!("**/{pattern}"),
+        };
+        for item in fs::glob(&pattern) {
+            if handle.check_interrupt() {
+                break;
+            }
+            handle.formatter.next_line().write_str(item.path.as_str());
+        }
+        Ok(())
+    }
+}
@@ -338,6 +338,12 @@ impl LittleManApp for ForEditor {
         if let Some(path) = machine.stack.try_pop::<String>() {
             self.load_file(path);
         }
+        machine.interactive(|machine| self.edit(machine));
+        Ok(())
+    }
+}
+impl ForEditor {
+    fn edit(&mut self, machine: &mut ForthMachine) {
         let formatter = machine.formatter.switch_to_text_mode();
         self.refresh_line_cache();
         self.draw_start_frame(formatter);
@@ -348,7 +354,5 @@ impl LittleManApp for ForEditor {
             }
         }
         formatter.clear_screen(VgaColor::Black).set_default_colors(VgaColorCombo::on_black(VgaColor::White)).enable_cursor().set_position((0,0));
-
-        Ok(())
     }
 }
@@ -21,13 +21,15 @@ impl DefaultInstall for ForRunner {
 }
 impl LittleManApp for ForRunner {
     fn run(&mut self, machine: &mut base::forth::ForthMachine) -> Result<(), ProgramError> {
+        let path = machine.stack.try_pop::<String>().ok_or(ProgramError::InvalidStartParameter)?;
         let script = {
-            let path = machine.stack.try_pop::<String>().ok_or(ProgramError::InvalidStartParameter)?;
-            let file = fs::get_file(PathString::from(path)).map_err(|_| ProgramError::FileSystemError)?.read_file().map_err(|_| ProgramError::FileSystemError)?;
+            let file = fs::get_file(PathString::from(path.clone())).map_err(|_| ProgramError::FileSystemError)?.read_file().map_err(|_| ProgramError::FileSystemError)?;
             file
         };
-        machine.add_instructions_to_end(&from_utf8(&script).map_err(|_| ProgramError::FileSystemError)?);
-        machine.run_to_end();
+        machine.run_source(&from_utf8(&script).map_err(|_| ProgramError::FileSystemError)?, Some(&path));
+        if machine.is_aborted() {
+            return Err(ProgramError::Custom("the script was aborted"));
+        }
         Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,48 @@
+use alloc::{boxed::Box, string::String};
+use base::{forth::ForthMachine, LittleManApp, ProgramError};
+use fs::{AppConstructor, DefaultInstall, PathString};
+
+use crate::read_source;
+
+/// Shows the lines containing a pattern, of the piped input or of a file
+///
+/// `dir | grep .for` or `grep word notes.txt`
+#[derive(Default)]
+pub struct Grep;
+pub struct GrepApp;
+
+impl DefaultInstall for Grep {
+    fn path() -> PathString {
+        PathString::from("grep.run")
+    }
+}
+impl AppConstructor for Grep {
+    fn instantiate(&self) -> Box<dyn LittleManApp> {
+        Box::new(GrepApp)
+    }
+}
+impl LittleManApp for GrepApp {
+    fn run(&mut self, handle: &mut ForthMachine) -> Result<(), ProgramError> {
+        let pattern = handle
+            .stack
+            .try_pop::<String>()
+            .ok_or(ProgramError::InvalidStartParameter)?;
+        let text = match handle.take_input() {
+            Some(text) => text,
+            None => {
+                let path = handle
+                    .stack
+                    .try_pop::<String>()
+                    .ok_or(ProgramError::Custom("nothing piped in and no file given"))?;
+                read_source(&PathString::from(path)).map_err(|_| ProgramError::FileSystemError)?
+            }
+        };
+        for line in text.lines().filter(|line| line.contains(pattern.as_str())) {
+            if handle.check_interrupt() {
+                break;
+            }
+            handle.formatter.next_line().write_str(line);
+        }
+        Ok(())
+    }
+}
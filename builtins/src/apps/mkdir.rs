@@ -0,0 +1,30 @@
+use alloc::boxed::Box;
+use base::{forth::ForthMachine, LittleManApp, ProgramError};
+use fs::{AppConstructor, DefaultInstall, PathString};
+
+use super::args::{file_error, pop_path};
+
+/// Creates a directory, `mkdir docs`
+#[derive(Default)]
+pub struct MakeDir;
+pub struct MakeDirApp;
+
+impl DefaultInstall for MakeDir {
+    fn path() -> PathString {
+        PathString::from("mkdir.run")
+    }
+}
+impl AppConstructor for MakeDir {
+    fn instantiate(&self) -> Box<dyn LittleManApp> {
+        Box::new(MakeDirApp)
+    }
+}
+impl LittleManApp for MakeDirApp {
+    fn run(&mut self, handle: &mut ForthMachine) -> Result<(), ProgramError> {
+        let path = pop_path(handle)?;
+        if fs::get_file(&path).is_ok() {
+            return Err(file_error(fs::FileSystemError::NameAlreadyExists));
+        }
+        fs::create_dir(path).map(|_| ()).map_err(file_error)
+    }
+}
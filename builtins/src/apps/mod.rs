@@ -1,14 +1,33 @@
+mod args;
+mod attrib;
+mod cat;
 mod cd;
 mod cls;
+mod cp;
 mod dir;
+mod find;
+mod grep;
 mod help;
+mod mkdir;
+mod mv;
+mod rm;
+mod touch;
 mod view;
 mod forrunner;
 mod foreditor;
+pub use attrib::*;
+pub use cat::*;
 pub use cd::*;
 pub use cls::*;
+pub use cp::*;
 pub use dir::*;
+pub use find::*;
+pub use grep::*;
 pub use help::*;
+pub use mkdir::*;
+pub use mv::*;
+pub use rm::*;
+pub use touch::*;
 pub use view::*;
 pub use foreditor::*;
 pub use forrunner::*;
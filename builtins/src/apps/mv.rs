@@ -0,0 +1,32 @@
+use alloc::boxed::Box;
+use base::{forth::ForthMachine, LittleManApp, ProgramError};
+use fs::{AppConstructor, DefaultInstall, PathString};
+
+use super::args::{file_error, pop_path};
+
+/// Moves or renames a file, `mv old.txt new.txt` or `mv notes.txt docs`
+#[derive(Default)]
+pub struct MoveFile;
+pub struct MoveFileApp;
+
+impl DefaultInstall for MoveFile {
+    fn path() -> PathString {
+        PathString::from("mv.run")
+    }
+}
+impl AppConstructor for MoveFile {
+    fn instantiate(&self) -> Box<dyn LittleManApp> {
+        Box::new(MoveFileApp)
+    }
+}
+impl LittleManApp for MoveFileApp {
+    fn run(&mut self, handle: &mut ForthMachine) -> Result<(), ProgramError> {
+        let from = pop_path(handle)?;
+        let to = pop_path(handle)?;
+        if fs::get_file(&to).is_ok_and(|file| file.is_directory()) {
+            fs::move_to(from, to).map_err(file_error)
+        } else {
+            fs::rename(from, to).map_err(file_error)
+        }
+    }
+}
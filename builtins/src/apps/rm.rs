@@ -0,0 +1,28 @@
+use alloc::boxed::Box;
+use base::{forth::ForthMachine, LittleManApp, ProgramError};
+use fs::{AppConstructor, DefaultInstall, PathString};
+
+use super::args::{file_error, pop_flag, pop_path};
+
+/// Removes a file, `rm notes.txt`, directories with everything in them are removed with `rm -r`
+#[derive(Default)]
+pub struct RemoveFile;
+pub struct RemoveFileApp;
+
+impl DefaultInstall for RemoveFile {
+    fn path() -> PathString {
+        PathString::from("rm.run")
+    }
+}
+impl AppConstructor for RemoveFile {
+    fn instantiate(&self) -> Box<dyn LittleManApp> {
+        Box::new(RemoveFileApp)
+    }
+}
+impl LittleManApp for RemoveFileApp {
+    fn run(&mut self, handle: &mut ForthMachine) -> Result<(), ProgramError> {
+        let recursive = pop_flag(handle, "-r");
+        let path = pop_path(handle)?;
+        fs::remove(path, recursive).map_err(file_error)
+    }
+}
@@ -0,0 +1,34 @@
+use alloc::boxed::Box;
+use base::{forth::ForthMachine, LittleManApp, ProgramError};
+use fs::{AppConstructor, DefaultInstall, FileSystemError, PathString};
+
+use super::args::{file_error, pop_path};
+
+/// Creates an empty data file, or marks an existing file as modified now, `touch notes.txt`
+#[derive(Default)]
+pub struct TouchFile;
+pub struct TouchFileApp;
+
+impl DefaultInstall for TouchFile {
+    fn path() -> PathString {
+        PathString::from("touch.run")
+    }
+}
+impl AppConstructor for TouchFile {
+    fn instantiate(&self) -> Box<dyn LittleManApp> {
+        Box::new(TouchFileApp)
+    }
+}
+impl LittleManApp for TouchFileApp {
+    fn run(&mut self, handle: &mut ForthMachine) -> Result<(), ProgramError> {
+        let path = pop_path(handle)?;
+        match fs::get_file_write(&path) {
+            Ok(mut file) => file.touch(),
+            Err(FileSystemError::FileNotFound(_)) => {
+                fs::create_data_file(path, &b""[..]).map(|_| ())
+            }
+            Err(err) => Err(err),
+        }
+        .map_err(file_error)
+    }
+}
@@ -74,7 +74,7 @@ impl LittleManApp for ViewInstance {
             string += "\"";
             string += program_path.as_str();
             string += "\" ";
-            string += "run";
+            string += "?run";
             string
         };
         machine.add_instructions_to_end(&program);
@@ -0,0 +1,93 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use base::{forth::ForthMachine, line_editor::Completer};
+use fs::{FileType, PathString};
+
+use crate::app_directories;
+
+/// Completes forth words and app names outside of strings, and app names and paths inside them
+pub struct ShellCompleter {
+    words: Vec<String>,
+    app_directories: Vec<PathString>,
+}
+
+impl ShellCompleter {
+    /// Completes the words and apps which the forth machine knows of
+    pub fn new(machine: &ForthMachine) -> Self {
+        Self {
+            words: machine
+                .word_names()
+                .into_iter()
+                .chain(crate::load_aliases().into_keys())
+                .collect(),
+            app_directories: app_directories(machine),
+        }
+    }
+
+    fn complete_app(&self, word: &str, candidates: &mut Vec<String>) {
+        if word.contains('/') {
+            return;
+        }
+        for directory in self.app_directories.iter().cloned() {
+            let Ok(entries) = fs::read_dir(directory) else {
+                continue;
+            };
+            for entry in entries.items() {
+                let name = entry.path.as_str();
+                if let (FileType::App, Some(app)) = (entry.filetype, name.strip_suffix(".run")) {
+                    if app.starts_with(word) {
+                        candidates.push(app.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    fn complete_path(word: &str, candidates: &mut Vec<String>) {
+        let (directory, name) = match word.rfind('/') {
+            Some(index) => (&word[..=index], &word[index + 1..]),
+            None => ("", word),
+        };
+        let listed = match directory.starts_with('/') {
+            true => PathString::from(directory),
+            false => fs::active_directory().append(&directory),
+        };
+        let Ok(entries) = fs::read_dir(listed.clean()) else {
+            return;
+        };
+        for entry in entries.items() {
+            if !entry.path.as_str().starts_with(name) {
+                continue;
+            }
+            let mut candidate = String::from(directory);
+            candidate += entry.path.as_str();
+            if let FileType::Directory = entry.filetype {
+                candidate.push('/');
+            }
+            candidates.push(candidate);
+        }
+    }
+}
+
+impl Completer for ShellCompleter {
+    fn complete(&self, word: &str, in_string: bool) -> Vec<String> {
+        let mut candidates = Vec::new();
+        if in_string {
+            self.complete_app(word, &mut candidates);
+            Self::complete_path(word, &mut candidates);
+        } else {
+            self.complete_app(word, &mut candidates);
+            candidates.extend(
+                self.words
+                    .iter()
+                    .filter(|name| name.starts_with(word))
+                    .cloned(),
+            );
+        }
+        candidates.sort();
+        candidates.dedup();
+        candidates
+    }
+}
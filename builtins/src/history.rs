@@ -0,0 +1,44 @@
+use alloc::{format, string::String, vec::Vec};
+use base::{forth::ForthMachine, line_editor::HISTORY_LIMIT};
+use fs::PathString;
+
+use crate::words::{read_source, write_data_file};
+
+/// Lines submitted at the prompt are saved here
+pub const HISTORY_PATH: &str = "/history.txt";
+
+/// Reads the history saved by earlier sessions, oldest line first
+pub fn load_history() -> Vec<String> {
+    read_source(&PathString::from(HISTORY_PATH))
+        .map(|source| source.lines().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Adds a submitted line to the saved history
+///
+/// Every console saves its lines to the same file, so only the new line is added to what's
+/// saved instead of each console writing its own history over the others'
+pub fn append_history(line: &str) {
+    let mut history = load_history();
+    if line.trim().is_empty() || history.last().map(String::as_str) == Some(line) {
+        return;
+    }
+    history.push(String::from(line));
+    let old = history.len().saturating_sub(HISTORY_LIMIT);
+    let mut data = String::new();
+    for line in &history[old..] {
+        data += line;
+        data += "\n";
+    }
+    let _ = write_data_file(PathString::from(HISTORY_PATH), data.into_bytes());
+}
+
+// HISTORY, lists the saved history
+pub(crate) fn forth_history(machine: &mut ForthMachine) {
+    for (i, line) in load_history().iter().enumerate() {
+        machine
+            .formatter
+            .next_line()
+            .write_str(&format!("{:>4}  {line}", i + 1));
+    }
+}
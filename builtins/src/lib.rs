@@ -1,9 +1,42 @@
 #![no_std]
 extern crate alloc;
+mod alias;
 mod apps;
+mod completion;
+mod history;
+mod prompt;
+mod shell;
+mod words;
+use alloc::vec::Vec;
+pub use alias::*;
 pub use apps::*;
+pub use completion::*;
+pub use history::*;
+pub use prompt::*;
+pub use shell::*;
+pub use words::*;
+use base::forth::ForthMachine;
 use fs::{FileSystemError, PathString};
 
+/// Where apps are searched for when PATH isn't set, `.` is the active directory
+pub const DEFAULT_PATH: &str = ".:/bin";
+/// Where `~` leads when HOME isn't set
+pub const DEFAULT_HOME: &str = "/";
+
+/// The directories in PATH, separated by `:`, which are searched for apps in order
+pub fn app_directories(machine: &ForthMachine) -> Vec<PathString> {
+    machine
+        .variable("PATH")
+        .unwrap_or(DEFAULT_PATH)
+        .split(':')
+        .filter(|directory| !directory.is_empty())
+        .map(|directory| match directory {
+            "." => fs::active_directory(),
+            _ => resolve(directory),
+        })
+        .collect()
+}
+
 pub fn install_all() -> Result<(), FileSystemError> {
     let active_path = fs::active_directory();
     let result = try_install();
@@ -23,10 +56,19 @@ fn try_install() -> Result<(), FileSystemError> {
     fs::set_active_directory(PathString::from("bin"));
     fs::install_app::<Help>()?;
     fs::install_app::<Dir>()?;
+    fs::install_app::<Find>()?;
+    fs::install_app::<Grep>()?;
     fs::install_app::<View>()?;
     fs::install_app::<ForEditorFile>()?;
     fs::install_app::<ForRunner>()?;
     fs::install_app::<ChangeDir>()?;
     fs::install_app::<ClearScreen>()?;
+    fs::install_app::<CopyFile>()?;
+    fs::install_app::<MoveFile>()?;
+    fs::install_app::<RemoveFile>()?;
+    fs::install_app::<MakeDir>()?;
+    fs::install_app::<TouchFile>()?;
+    fs::install_app::<Attrib>()?;
+    fs::install_app::<PrintFile>()?;
     Ok(())
 }
@@ -0,0 +1,76 @@
+use alloc::{format, string::String};
+use base::{
+    display::{VgaColor, VgaColorCombo},
+    forth::ForthMachine,
+    line_editor::Prompt,
+};
+
+/// The prompt used when PROMPT isn't set
+pub const DEFAULT_PROMPT: &str = "%d > ";
+
+/// Builds the prompt from the PROMPT variable, where
+///
+/// - `%d` is the active directory
+/// - `%t` is the time since the system started
+/// - `%s` is the status of the last app which ran
+/// - `%n` is the depth of the stack
+/// - `%{yellow}` colors the text after it, `%{}` goes back to the normal color
+/// - `%%` is a percent sign
+pub fn expand_prompt(machine: &ForthMachine, uptime_seconds: u64) -> Prompt {
+    let template = machine.variable("PROMPT").unwrap_or(DEFAULT_PROMPT);
+    let mut prompt = Prompt::default();
+    let mut color = None;
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            prompt.push(c.encode_utf8(&mut [0; 4]), color);
+            continue;
+        }
+        match chars.next() {
+            Some('d') => prompt.push(fs::active_directory().as_str(), color),
+            Some('t') => prompt.push(&format_time(uptime_seconds), color),
+            Some('s') => prompt.push(machine.variable("STATUS").unwrap_or("0"), color),
+            Some('n') => prompt.push(&format!("{}", machine.stack.len()), color),
+            Some('{') => {
+                let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                color = color_by_name(&name).map(VgaColorCombo::on_black);
+            }
+            Some('%') => prompt.push("%", color),
+            Some(other) => {
+                prompt.push("%", color);
+                prompt.push(other.encode_utf8(&mut [0; 4]), color);
+            }
+            None => prompt.push("%", color),
+        }
+    }
+    prompt
+}
+
+/// Formats seconds since the system started as `hh:mm:ss`
+pub(crate) fn format_time(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    format!("{hours:02}:{minutes:02}:{seconds:02}")
+}
+
+fn color_by_name(name: &str) -> Option<VgaColor> {
+    let color = match name {
+        "black" => VgaColor::Black,
+        "blue" => VgaColor::Blue,
+        "green" => VgaColor::Green,
+        "cyan" => VgaColor::Cyan,
+        "red" => VgaColor::Red,
+        "magenta" => VgaColor::Magenta,
+        "brown" => VgaColor::Brown,
+        "lightgray" => VgaColor::LightGray,
+        "darkgray" => VgaColor::DarkGray,
+        "lightblue" => VgaColor::LightBlue,
+        "lightgreen" => VgaColor::LightGreen,
+        "lightcyan" => VgaColor::LightCyan,
+        "lightred" => VgaColor::LightRed,
+        "pink" => VgaColor::Pink,
+        "yellow" => VgaColor::Yellow,
+        "white" => VgaColor::White,
+        _ => return None,
+    };
+    Some(color)
+}
@@ -0,0 +1,187 @@
+use alloc::{format, string::String, vec::Vec};
+use base::forth::ForthMachine;
+use core::{iter::Peekable, str::Chars};
+use fs::PathString;
+
+use crate::{app_directories, resolve, write_output};
+
+/// A line written as `app arg1 "arg 2"`, the everyday way of starting apps
+pub struct Command {
+    pub app: String,
+    pub arguments: Vec<String>,
+}
+
+impl Command {
+    /// Reads the tokens as a command if the first is an app and not a forth word
+    fn from_tokens(tokens: &[(String, bool)], machine: &ForthMachine) -> Option<Self> {
+        let ((app, false), arguments) = tokens.split_first()? else {
+            return None;
+        };
+        if machine.word_names().contains(app) || find_app(machine, app).is_none() {
+            return None;
+        }
+        Some(Self {
+            app: app.clone(),
+            arguments: arguments.iter().map(|(token, _)| token.clone()).collect(),
+        })
+    }
+
+    /// The same command as forth, `help eng` becomes `"eng" "help" ?run`
+    ///
+    /// Apps pop their first argument first, so arguments are pushed in reverse
+    pub fn to_forth(&self) -> String {
+        let mut source = String::new();
+        for argument in self.arguments.iter().rev().chain([&self.app]) {
+            source += &format!("\"{}\" ", argument.replace('"', "\\\""));
+        }
+        source += "?run";
+        source
+    }
+}
+
+/// Commands joined by `|`, the text output of each one is the input of the next
+pub struct Pipeline {
+    pub commands: Vec<Command>,
+    /// Where the output of the last command goes instead of the screen
+    pub redirect: Option<Redirect>,
+}
+
+/// `> path` writes output into a file, `>> path` adds it to the end of the file
+pub struct Redirect {
+    pub path: String,
+    pub append: bool,
+}
+
+impl Redirect {
+    fn write(&self, machine: &mut ForthMachine, output: String) {
+        let path = resolve(&self.path);
+        if let Err(err) = write_output(path, output, self.append) {
+            machine.error(&format!("could not write to {}: {err:?}", self.path));
+        }
+    }
+}
+
+impl Pipeline {
+    /// Reads a line in command syntax, a line where any command doesn't start
+    /// with an app is left to forth
+    pub fn parse(line: &str, machine: &ForthMachine) -> Option<Self> {
+        let mut tokens = split_arguments(line, machine)?;
+        let redirect = match tokens.as_slice() {
+            [.., (operator, false), (path, _)] if operator == ">" || operator == ">>" => {
+                Some(Redirect {
+                    path: path.clone(),
+                    append: operator == ">>",
+                })
+            }
+            _ => None,
+        };
+        if redirect.is_some() {
+            tokens.truncate(tokens.len() - 2);
+        }
+        let commands = tokens
+            .split(|(token, quoted)| !quoted && token == "|")
+            .map(|tokens| Command::from_tokens(tokens, machine))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { commands, redirect })
+    }
+
+    pub fn run(&self, machine: &mut ForthMachine) {
+        let last = self.commands.len() - 1;
+        for (i, command) in self.commands.iter().enumerate() {
+            let captured = i != last || self.redirect.is_some();
+            let outer = match captured {
+                true => machine.formatter.capture_output(Some(String::new())),
+                false => None,
+            };
+            machine.set_command_arguments(Some(command.arguments.len()));
+            machine.add_instructions_to_end(&command.to_forth());
+            machine.run_to_end();
+            machine.set_command_arguments(None);
+            if captured {
+                let output = machine.formatter.capture_output(outer);
+                match &self.redirect {
+                    Some(redirect) if i == last => {
+                        redirect.write(machine, output.unwrap_or_default())
+                    }
+                    _ => machine.set_input(output),
+                }
+            }
+            if machine.is_aborted() {
+                break;
+            }
+        }
+        // Input the last app didn't read isn't left for whatever runs next
+        machine.set_input(None);
+    }
+}
+
+/// Finds the app with this name in the [app_directories]
+pub fn find_app(machine: &ForthMachine, name: &str) -> Option<PathString> {
+    let file_name = PathString::from(name).add_extension("run");
+    app_directories(machine)
+        .into_iter()
+        .map(|directory| directory.append(&file_name))
+        .find(|path| fs::get_file(path.clone()).is_ok())
+}
+
+/// Splits a line on whitespace, `|`, `>` and `>>`, keeping quoted parts together and
+/// telling whether each part was quoted. Returns None on an unclosed quote
+///
+/// `$NAME` is replaced with the environment variable, also inside quotes
+fn split_arguments(line: &str, machine: &ForthMachine) -> Option<Vec<(String, bool)>> {
+    let mut arguments = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            return Some(arguments);
+        };
+        if first == '"' {
+            chars.next();
+            let mut argument = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' if chars.peek() == Some(&'"') => argument.push(chars.next()?),
+                    '$' => expand_variable(&mut chars, machine, &mut argument),
+                    c => argument.push(c),
+                }
+            }
+            arguments.push((argument, true));
+        } else if first == '|' {
+            chars.next();
+            arguments.push((String::from("|"), false));
+        } else if first == '>' {
+            chars.next();
+            match chars.next_if_eq(&'>') {
+                Some(_) => arguments.push((String::from(">>"), false)),
+                None => arguments.push((String::from(">"), false)),
+            }
+        } else {
+            let mut argument = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !"|>".contains(*c)) {
+                match c {
+                    '$' => expand_variable(&mut chars, machine, &mut argument),
+                    c => argument.push(c),
+                }
+            }
+            if argument.starts_with('~') {
+                let home = machine.variable("HOME").unwrap_or(crate::DEFAULT_HOME);
+                argument = String::from(PathString::from(argument).expand_home(&home).as_str());
+            }
+            arguments.push((argument, false));
+        }
+    }
+}
+
+/// Reads the name after a `$` and adds the value of that variable, a lone `$` is kept
+fn expand_variable(chars: &mut Peekable<Chars>, machine: &ForthMachine, argument: &mut String) {
+    let mut name = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+        name.push(c);
+    }
+    match name.is_empty() {
+        true => argument.push('$'),
+        false => *argument += machine.variable(&name).unwrap_or_default(),
+    }
+}
@@ -0,0 +1,174 @@
+use alloc::{format, string::String, vec::Vec};
+use base::forth::{ExecutionToken, ForthMachine};
+use fs::{FileSystemError, PathString};
+
+use crate::{
+    alias::{forth_alias, forth_aliases, forth_unalias},
+    history::forth_history,
+};
+
+/// Definitions in this file are loaded automatically when the system starts
+pub const SESSION_WORDS_PATH: &str = "/words.for";
+
+/// Adds the builtin words which need the file system
+pub fn install_words(machine: &mut ForthMachine) {
+    machine.insert_default_word("save-words", &forth_save_words);
+    machine.insert_default_word("load-words", &forth_load_words);
+    machine.insert_default_word("include", &forth_include);
+    machine.insert_default_word("require", &forth_require);
+    machine.insert_default_word("history", &forth_history);
+    machine.insert_default_word("to-file", &forth_to_file);
+    machine.insert_default_word("append-to-file", &forth_append_to_file);
+    machine.insert_default_word("alias", &forth_alias);
+    machine.insert_default_word("unalias", &forth_unalias);
+    machine.insert_default_word("aliases", &forth_aliases);
+    if machine.variable("HOME").is_none() {
+        machine.set_variable("HOME", crate::DEFAULT_HOME);
+    }
+    if machine.variable("PATH").is_none() {
+        machine.set_variable("PATH", crate::DEFAULT_PATH);
+    }
+    if machine.variable("PROMPT").is_none() {
+        machine.set_variable("PROMPT", crate::DEFAULT_PROMPT);
+    }
+}
+
+/// Loads the session definitions file, if there is one
+pub fn load_session_words(machine: &mut ForthMachine) {
+    if let Ok(source) = read_source(&PathString::from(SESSION_WORDS_PATH)) {
+        machine.add_file_to_end(&source, SESSION_WORDS_PATH);
+        machine.run_to_end();
+    }
+}
+
+// "path" SAVE-WORDS
+fn forth_save_words(machine: &mut ForthMachine) {
+    let Some(path) = machine.stack.try_pop::<String>() else {
+        machine.error("SAVE-WORDS expects a path");
+        return;
+    };
+    let source = machine.dictionary_source();
+    if let Err(err) = write_data_file(resolve(&path), source.into_bytes()) {
+        machine.error(&format!("SAVE-WORDS could not write {path}: {err:?}"));
+    }
+}
+
+// "path" LOAD-WORDS
+fn forth_load_words(machine: &mut ForthMachine) {
+    let Some(path) = machine.stack.try_pop::<String>() else {
+        machine.error("LOAD-WORDS expects a path");
+        return;
+    };
+    let path = resolve(&path);
+    match read_source(&path) {
+        Ok(source) => machine.run_source(&source, Some(path.as_str())),
+        Err(err) => machine.error(&format!(
+            "LOAD-WORDS could not read {}: {err:?}",
+            path.as_str()
+        )),
+    }
+}
+
+// "path" INCLUDE
+fn forth_include(machine: &mut ForthMachine) {
+    include(machine, "INCLUDE", false);
+}
+
+// "path" REQUIRE, only includes files which haven't been included before
+fn forth_require(machine: &mut ForthMachine) {
+    include(machine, "REQUIRE", true);
+}
+
+fn include(machine: &mut ForthMachine, word: &str, once: bool) {
+    let Some(path) = machine.stack.try_pop::<String>() else {
+        machine.error(&format!("{word} expects a path"));
+        return;
+    };
+    let path = resolve_from_source(machine, &path);
+    if once && machine.is_included(path.as_str()) {
+        return;
+    }
+    match read_source(&path) {
+        // Files which couldn't be read are tried again by the next REQUIRE
+        Ok(source) => {
+            machine.mark_included(path.as_str());
+            machine.run_source(&source, Some(path.as_str()))
+        }
+        Err(err) => machine.error(&format!("{word} could not read {}: {err:?}", path.as_str())),
+    }
+}
+
+// xt "path" TO-FILE, writes what xt prints into the file instead of the screen
+fn forth_to_file(machine: &mut ForthMachine) {
+    redirect(machine, "TO-FILE", false);
+}
+
+// xt "path" APPEND-TO-FILE, adds what xt prints to the end of the file
+fn forth_append_to_file(machine: &mut ForthMachine) {
+    redirect(machine, "APPEND-TO-FILE", true);
+}
+
+fn redirect(machine: &mut ForthMachine, word: &str, append: bool) {
+    let Some(path) = machine.stack.try_pop::<String>() else {
+        machine.error(&format!("{word} expects an execution token and a path"));
+        return;
+    };
+    let Some(xt) = machine.stack.try_pop::<ExecutionToken>() else {
+        machine.error(&format!("{word} expects an execution token and a path"));
+        return;
+    };
+    let path = resolve_from_source(machine, &path);
+    let outer = machine.formatter.capture_output(Some(String::new()));
+    machine.execute(xt);
+    let output = machine.formatter.capture_output(outer).unwrap_or_default();
+    if let Err(err) = write_output(path.clone(), output, append) {
+        machine.error(&format!(
+            "{word} could not write {}: {err:?}",
+            path.as_str()
+        ));
+    }
+}
+
+/// Relative paths are resolved against the directory of the file which is running
+fn resolve_from_source(machine: &ForthMachine, path: &str) -> PathString {
+    let directory = machine
+        .location()
+        .file
+        .as_ref()
+        .and_then(|file| PathString::from(&**file).parent());
+    match directory {
+        Some(directory) => directory.join(&path).clean(),
+        None => resolve(path),
+    }
+}
+
+/// Paths starting with / are absolute, everything else is relative to the active directory
+pub(crate) fn resolve(path: &str) -> PathString {
+    fs::active_directory().join(&path).clean()
+}
+
+pub(crate) fn read_source(path: &PathString) -> Result<String, FileSystemError> {
+    let data = fs::get_file(path)?.read_file()?;
+    String::from_utf8(data).map_err(|_| FileSystemError::IncorrectFileType("file is not text"))
+}
+
+/// Writes captured output into a file, after what's already in it when appending
+pub(crate) fn write_output(
+    path: PathString,
+    output: String,
+    append: bool,
+) -> Result<(), FileSystemError> {
+    if append {
+        if let Ok(mut file) = fs::get_file_write(&path) {
+            return file.append(output.as_bytes());
+        }
+    }
+    write_data_file(path, output.into_bytes())
+}
+
+pub(crate) fn write_data_file(path: PathString, data: Vec<u8>) -> Result<(), FileSystemError> {
+    match fs::get_file_write(&path) {
+        Ok(mut file) => file.write_file(&data),
+        Err(_) => fs::create_data_file(path, data).map(|_| ()),
+    }
+}
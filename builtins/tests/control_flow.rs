@@ -0,0 +1,46 @@
+use base::{
+    display::{DefaultVgaBuffer, DefaultVgaWriter, ScreenBuffer, UniversalVgaFormatter, VgaChar},
+    forth::ForthMachine,
+};
+
+/// Runs a line on a machine which writes to a screen in memory, giving the text on the screen
+fn run(machine: &mut ForthMachine, line: &str) -> String {
+    let screen: &'static mut DefaultVgaBuffer = Box::leak(Box::new(ScreenBuffer {
+        chars: [[VgaChar::BLANK; 80]; 25],
+    }));
+    let shown = screen as *const DefaultVgaBuffer;
+    machine.formatter = UniversalVgaFormatter::new(DefaultVgaWriter::new(screen));
+    machine.add_instructions_to_end(&line);
+    machine.run_to_end();
+    let chars = unsafe { &(*shown).chars };
+    chars
+        .iter()
+        .flatten()
+        .map(|char| char.byte() as char)
+        .collect()
+}
+
+fn machine() -> ForthMachine {
+    let mut machine = ForthMachine::default();
+    builtins::install_words(&mut machine);
+    machine
+}
+
+#[test]
+fn upper_case_begin_again_loops() {
+    let mut machine = machine();
+    machine.set_instruction_budget(Some(10));
+    let shown = run(&mut machine, "BEGIN 1 AGAIN");
+    assert!(!shown.contains("unknown word"));
+    assert!(shown.contains("budget"));
+    assert!(machine.stack.len() > 1);
+}
+
+#[test]
+fn upper_case_then_is_a_marker() {
+    let mut machine = machine();
+    let shown = run(&mut machine, "1 Then 2 THEN");
+    assert!(!shown.contains("ERROR"));
+    assert_eq!(machine.stack.try_pop::<isize>(), Some(2));
+    assert_eq!(machine.stack.try_pop::<isize>(), Some(1));
+}
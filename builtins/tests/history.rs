@@ -0,0 +1,13 @@
+use builtins::{append_history, load_history};
+
+#[test]
+fn every_console_adds_to_the_same_history() {
+    fs::start();
+    // Lines from one console are added after those of the others, empty and repeated ones are not
+    append_history("1 2 +");
+    append_history("dir");
+    append_history("dir");
+    append_history("  ");
+    append_history("words");
+    assert_eq!(load_history(), ["1 2 +", "dir", "words"]);
+}
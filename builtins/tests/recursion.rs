@@ -0,0 +1,35 @@
+use base::{
+    display::{DefaultVgaBuffer, DefaultVgaWriter, ScreenBuffer, UniversalVgaFormatter, VgaChar},
+    forth::ForthMachine,
+};
+
+/// A machine which writes to a screen in memory instead of the VGA buffer
+fn machine() -> ForthMachine {
+    let screen: &'static mut DefaultVgaBuffer = Box::leak(Box::new(ScreenBuffer {
+        chars: [[VgaChar::BLANK; 80]; 25],
+    }));
+    let mut machine = ForthMachine::default();
+    machine.formatter = UniversalVgaFormatter::new(DefaultVgaWriter::new(screen));
+    builtins::install_words(&mut machine);
+    machine
+}
+
+#[test]
+fn endless_recursion_is_aborted() {
+    let mut machine = machine();
+    machine.add_instructions_to_end(&": forever forever : forever 1");
+    machine.run_to_end();
+    assert!(machine.is_aborted());
+    assert!(machine.stack.is_empty());
+}
+
+#[test]
+fn the_next_line_runs_after_endless_recursion() {
+    let mut machine = machine();
+    machine.add_instructions_to_end(&": forever forever : forever");
+    machine.run_to_end();
+    machine.add_instructions_to_end(&": twice dup + : 21 twice");
+    machine.run_to_end();
+    assert!(!machine.is_aborted());
+    assert_eq!(machine.stack.try_pop::<isize>(), Some(42));
+}
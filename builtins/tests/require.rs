@@ -0,0 +1,34 @@
+use base::{
+    display::{DefaultVgaBuffer, DefaultVgaWriter, ScreenBuffer, UniversalVgaFormatter, VgaChar},
+    forth::ForthMachine,
+};
+use fs::PathString;
+
+/// A machine which writes to a screen in memory instead of the VGA buffer
+fn machine() -> ForthMachine {
+    let screen: &'static mut DefaultVgaBuffer = Box::leak(Box::new(ScreenBuffer {
+        chars: [[VgaChar::BLANK; 80]; 25],
+    }));
+    let mut machine = ForthMachine::default();
+    machine.formatter = UniversalVgaFormatter::new(DefaultVgaWriter::new(screen));
+    builtins::install_words(&mut machine);
+    machine
+}
+
+fn run(machine: &mut ForthMachine, line: &str) {
+    machine.add_instructions_to_end(&line);
+    machine.run_to_end();
+}
+
+#[test]
+fn require_tries_again_after_the_file_was_missing() {
+    fs::start();
+    let mut machine = machine();
+    run(&mut machine, "\"/answer.for\" require");
+    fs::create_data_file(PathString::from("/answer.for"), b": answer 42 :".to_vec()).unwrap();
+    run(&mut machine, "\"/answer.for\" require answer");
+    assert_eq!(machine.stack.try_pop::<isize>(), Some(42));
+    // Once it has been included it isn't again
+    run(&mut machine, "\"/answer.for\" require");
+    assert!(machine.stack.is_empty());
+}
@@ -5,46 +5,129 @@ use alloc::{
 use hashbrown::HashMap;
 use spin::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use crate::{File, FileMetadata, FileType, KaggFile, PathString};
+use crate::{
+    is_valid_name, Attributes, File, FileInfo, FileMetadata, FileSystemError, FileType, KaggFile,
+    PathString, Wait,
+};
+
+/// A file in a directory together with what can be known about it while it's locked
+struct Entry {
+    file: RwLock<KaggFile>,
+    filetype: FileType,
+    info: FileInfo,
+}
 
 #[derive(Default)]
-pub struct Directory(HashMap<String, RwLock<KaggFile>>);
+pub struct Directory(HashMap<String, Entry>);
 impl Directory {
-    pub fn fetch(&self, file: &str) -> Option<RwLockReadGuard<'_, KaggFile>> {
-        self.0.get(file).map(|s| s.try_read()).flatten()
+    /// The creation and modification times and attributes of a file
+    pub fn info(&self, file: &str) -> Option<&FileInfo> {
+        self.0.get(file).map(|s| &s.info)
+    }
+    /// How many files are in the directory
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Locks a file for reading, waiting as long as `wait` says
+    ///
+    /// Files which don't exist are `FileNotFound` and ones another handle has locked are `Busy`
+    pub fn try_fetch(
+        &self,
+        file: &str,
+        wait: Wait,
+    ) -> Result<RwLockReadGuard<'_, KaggFile>, FileSystemError> {
+        let entry = self
+            .0
+            .get(file)
+            .ok_or(FileSystemError::FileNotFound("no file with that name"))?;
+        wait.acquire(|| entry.file.try_read())
     }
-    pub fn fetch_write(&self, file: &str) -> Option<RwLockWriteGuard<'_, KaggFile>> {
-        self.0.get(file).map(|s| s.try_write()).flatten()
+    /// Locks a file for writing, waiting as long as `wait` says, see [Self::try_fetch]
+    pub fn try_fetch_write(
+        &self,
+        file: &str,
+        wait: Wait,
+    ) -> Result<RwLockWriteGuard<'_, KaggFile>, FileSystemError> {
+        let entry = self
+            .0
+            .get(file)
+            .ok_or(FileSystemError::FileNotFound("no file with that name"))?;
+        wait.acquire(|| entry.file.try_write())
     }
+    /// Lists every file, including ones which other handles have locked
     pub fn read_all(&self) -> DirRead {
         DirRead(
             self.0
                 .iter()
-                .filter_map(|(str, i)| {
-                    i.try_read()
-                        .map(|succ| match &*succ {
-                            KaggFile::Directory(_) => Some(FileMetadata {
-                                path: PathString(str.to_string()),
-                                filetype: FileType::Directory,
-                            }),
-                            KaggFile::Data(_) => Some(FileMetadata {
-                                path: PathString(str.to_string()),
-                                filetype: FileType::Data,
-                            }),
-                            KaggFile::App(_) => Some(FileMetadata {
-                                path: PathString(str.to_string()),
-                                filetype: FileType::App,
-                            }),
-                            _ => None,
-                        })
-                        .flatten()
+                .map(|(str, entry)| {
+                    FileMetadata::new(PathString(str.to_string()), entry.filetype, &entry.info)
                 })
                 .collect(),
         )
     }
-    pub fn add_file(&mut self, file: File) {
-        let File { data, name } = file;
-        self.0.insert(name, RwLock::new(data));
+    /// Adds a file to the directory unless another file already has its name or the name isn't valid
+    ///
+    /// The file is given back when it can't be added
+    pub fn add_file(&mut self, file: File) -> Result<(), (FileSystemError, File)> {
+        if !is_valid_name(&file.name) {
+            return Err((FileSystemError::InvalidName, file));
+        }
+        if self.0.contains_key(&file.name) {
+            return Err((FileSystemError::NameAlreadyExists, file));
+        }
+        let File { data, name, info } = file;
+        self.0.insert(
+            name,
+            Entry {
+                filetype: data.file_type(),
+                file: RwLock::new(data),
+                info,
+            },
+        );
+        Ok(())
+    }
+    /// Takes a file out of the directory, directories have to be empty unless `recursive` is set
+    /// and read-only files stay, as do directories with read-only files anywhere below them
+    ///
+    /// Nothing inside a directory which is borrowed mutably can have a handle open
+    pub fn take_file(&mut self, name: &str, recursive: bool) -> Result<File, FileSystemError> {
+        let entry = self
+            .0
+            .get_mut(name)
+            .ok_or(FileSystemError::FileNotFound("no file with that name"))?;
+        if entry.info.attributes().contains(Attributes::READ_ONLY) {
+            return Err(FileSystemError::ReadOnly);
+        }
+        if let KaggFile::Directory(directory) = entry.file.get_mut() {
+            if !recursive && !directory.0.is_empty() {
+                return Err(FileSystemError::DirectoryNotEmpty);
+            }
+            if directory.contains_read_only() {
+                return Err(FileSystemError::ReadOnly);
+            }
+        }
+        let (name, entry) = self.0.remove_entry(name).unwrap();
+        Ok(File {
+            data: entry.file.into_inner(),
+            name,
+            info: entry.info,
+        })
+    }
+}
+
+impl Directory {
+    /// Whether any file in the directory or below it is read-only
+    fn contains_read_only(&mut self) -> bool {
+        self.0.values_mut().any(|entry| {
+            entry.info.attributes().contains(Attributes::READ_ONLY)
+                || match entry.file.get_mut() {
+                    KaggFile::Directory(directory) => directory.contains_read_only(),
+                    _ => false,
+                }
+        })
     }
 }
 
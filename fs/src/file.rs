@@ -10,7 +10,6 @@ pub enum KaggFile {
     Directory(Directory),
     Data(Cow<'static, [u8]>),
     App(Box<dyn AppConstructor>),
-    Deleted,
 }
 impl KaggFile {
     pub fn is_directory(&self) -> bool {
@@ -19,12 +18,19 @@ impl KaggFile {
             _ => false,
         }
     }
-    pub fn file_type(&self) -> Option<FileType> {
+    /// Bytes in a data file, files in a directory and nothing for an app
+    pub fn size(&self) -> usize {
         match self {
-            KaggFile::Directory(_) => Some(FileType::Directory),
-            KaggFile::Data(_) => Some(FileType::Data),
-            KaggFile::App(_) => Some(FileType::App),
-            KaggFile::Deleted => None,
+            KaggFile::Directory(directory) => directory.len(),
+            KaggFile::Data(data) => data.len(),
+            KaggFile::App(_) => 0,
+        }
+    }
+    pub fn file_type(&self) -> FileType {
+        match self {
+            KaggFile::Directory(_) => FileType::Directory,
+            KaggFile::Data(_) => FileType::Data,
+            KaggFile::App(_) => FileType::App,
         }
     }
 }
@@ -3,9 +3,13 @@ use core::marker::PhantomData;
 use alloc::{boxed::Box, vec::Vec};
 use base::LittleManApp;
 
+use embedded_io::{ErrorType, Read, Seek, SeekFrom, Write};
 use spin::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use crate::{DirRead, Directory, File, FileSystemError, KaggFile, PathString, FILE_SYSTEM};
+use crate::{
+    Attributes, DirRead, Directory, File, FileInfo, FileMetadata, FileSystemError, FileType,
+    KaggFile, PathString, Wait, FILE_SYSTEM, ROOT_INFO,
+};
 
 /// Definitions for the different FileHandle priviliges
 pub enum WritePriviliges {}
@@ -20,10 +24,14 @@ impl FileHandlePriviliges for ReadPriviliges {}
 /// This uses recursive locking in order to ensure safety in case of multiple handles coexisting
 ///
 /// Depending on the handles priviliges you will be given access to appropriate methods
+///
+/// Data files can also be read and written a part at a time from a cursor, which starts
+/// at the beginning of the file
 pub struct LittleFileHandle<'a, T: FileHandlePriviliges> {
     _filesystem: RwLockReadGuard<'a, Option<RwLock<Directory>>>,
     locks: FileHandleLocks<'a>,
     path: PathString,
+    position: usize,
     _phantom: PhantomData<T>,
 }
 
@@ -38,6 +46,16 @@ impl<'a, T: FileHandlePriviliges> LittleFileHandle<'a, T> {
         self.locks.attempt_dir(|_| ()).is_ok()
     }
 
+    /// The size, times and attributes of this file
+    pub fn stat(&self) -> FileMetadata {
+        // Only the root directory has no file to look at
+        let filetype = self
+            .locks
+            .attempt(|file| file.file_type())
+            .unwrap_or(FileType::Directory);
+        FileMetadata::new(self.path.clone(), filetype, self.locks.info())
+    }
+
     /// Attempt to launch this file as an app
     pub fn launch_app(&self) -> Result<Box<dyn LittleManApp>, FileSystemError> {
         self.locks
@@ -49,9 +67,6 @@ impl<'a, T: FileHandlePriviliges> LittleFileHandle<'a, T> {
                 KaggFile::Directory(_) => Err(FileSystemError::IncorrectFileType(
                     "trying to open directory",
                 )),
-                KaggFile::Deleted => Err(FileSystemError::IncorrectFileType(
-                    "trying to open deleted file",
-                )),
             })
             .map_err(|_err| FileSystemError::Busy)
             .flatten()
@@ -73,24 +88,65 @@ impl<'a, T: FileHandlePriviliges> LittleFileHandle<'a, T> {
                 KaggFile::Directory(_) => Err(FileSystemError::IncorrectFileType(
                     "trying to open directory",
                 )),
-                KaggFile::Deleted => Err(FileSystemError::IncorrectFileType(
-                    "trying to open deleted file",
-                )),
             })
             .map_err(|_err| FileSystemError::Busy)
             .flatten()
     }
+
+    /// Reads from the cursor into `buffer` and moves the cursor past what was read
+    ///
+    /// Returns how many bytes were read, which is 0 at the end of the file
+    pub fn read(&mut self, buffer: &mut [u8]) -> Result<usize, FileSystemError> {
+        let position = self.position;
+        let read = self.data(|data| {
+            let rest = data.get(position..).unwrap_or_default();
+            let read = rest.len().min(buffer.len());
+            buffer[..read].copy_from_slice(&rest[..read]);
+            read
+        })?;
+        self.position += read;
+        Ok(read)
+    }
+
+    /// Moves the cursor, which may be put past the end of the file
+    ///
+    /// Returns the new position from the beginning of the file
+    pub fn seek(&mut self, position: SeekFrom) -> Result<u64, FileSystemError> {
+        let position = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => {
+                (self.data(|data| data.len())? as u64).checked_add_signed(offset)
+            }
+            SeekFrom::Current(offset) => (self.position as u64).checked_add_signed(offset),
+        }
+        .ok_or(FileSystemError::InvalidSeek)?;
+        self.position = position as usize;
+        Ok(position)
+    }
+
+    fn data<R>(&self, op: impl FnOnce(&[u8]) -> R) -> Result<R, FileSystemError> {
+        self.locks
+            .attempt(|file| match file {
+                KaggFile::Data(data) => Ok(op(data)),
+                _ => Err(FileSystemError::IncorrectFileType(
+                    "file is not a data file",
+                )),
+            })
+            .map_err(|_err| FileSystemError::IncorrectFileType("file is not a data file"))
+            .flatten()
+    }
 }
 
 /// Methods which only file handles with writing privliges may want to use
 impl<'a> LittleFileHandle<'a, WritePriviliges> {
-    pub fn new(path: PathString) -> Result<Self, FileSystemError> {
+    pub fn new(path: PathString, wait: Wait) -> Result<Self, FileSystemError> {
         let path = path.clean();
         let filesystem = FILE_SYSTEM.0.read();
         let locks = if path.components().count() == 1 {
             unsafe {
                 FileHandleLocks::write_root(
                     filesystem.as_ref().ok_or(FileSystemError::PointerError)? as *const _,
+                    wait,
                 )?
             }
         } else {
@@ -98,6 +154,7 @@ impl<'a> LittleFileHandle<'a, WritePriviliges> {
                 FileHandleLocks::write(
                     filesystem.as_ref().ok_or(FileSystemError::PointerError)? as *const _,
                     &path,
+                    wait,
                 )?
             }
         };
@@ -105,6 +162,7 @@ impl<'a> LittleFileHandle<'a, WritePriviliges> {
             _filesystem: filesystem,
             locks,
             path,
+            position: 0,
             _phantom: PhantomData,
         })
     }
@@ -113,6 +171,7 @@ impl<'a> LittleFileHandle<'a, WritePriviliges> {
     ///
     /// This may be unsuccessful if the file isn't a data file or is unavailable in some other way
     pub fn write_file(&mut self, insert: &[u8]) -> Result<(), FileSystemError> {
+        self.check_writable()?;
         self.locks
             .attempt_mut(|file| match file {
                 KaggFile::Data(data) => {
@@ -124,14 +183,159 @@ impl<'a> LittleFileHandle<'a, WritePriviliges> {
             })
             .map_err(|_err| ())
             .flatten()
-            .map_err(|_err| FileSystemError::PointerError)
+            .map_err(|_err| FileSystemError::PointerError)?;
+        self.changed();
+        Ok(())
     }
 
-    /// Add a new file inside this one if it's a directory
-    pub fn add_child(&mut self, file: File) -> Result<(), FileSystemError> {
-        self.locks
-            .attempt_dir_mut(|dir| dir.add_file(file))
-            .map_err(|_err| FileSystemError::Busy)
+    /// Writes `buffer` at the cursor and moves the cursor past it
+    ///
+    /// What was there is overwritten and the file grows if needed, past its end it's filled with zeroes
+    pub fn write(&mut self, buffer: &[u8]) -> Result<usize, FileSystemError> {
+        let position = self.position;
+        self.data_mut(|data| {
+            if data.len() < position {
+                data.resize(position, 0);
+            }
+            let overwritten = (data.len() - position).min(buffer.len());
+            data[position..position + overwritten].copy_from_slice(&buffer[..overwritten]);
+            data.extend_from_slice(&buffer[overwritten..]);
+        })?;
+        self.position += buffer.len();
+        Ok(buffer.len())
+    }
+
+    /// Adds `buffer` to the end of the file and moves the cursor there
+    pub fn append(&mut self, buffer: &[u8]) -> Result<(), FileSystemError> {
+        self.position = self.data_mut(|data| {
+            data.extend_from_slice(buffer);
+            data.len()
+        })?;
+        Ok(())
+    }
+
+    /// Cuts the file to `length` bytes, the cursor is moved back if it was past the new end
+    pub fn truncate(&mut self, length: usize) -> Result<(), FileSystemError> {
+        self.data_mut(|data| data.truncate(length))?;
+        self.position = self.position.min(length);
+        Ok(())
+    }
+
+    /// Changes the data of this file, which has to be writable
+    ///
+    /// A file compiled into the system is copied the first time it's changed
+    fn data_mut<R>(&mut self, op: impl FnOnce(&mut Vec<u8>) -> R) -> Result<R, FileSystemError> {
+        self.check_writable()?;
+        let result = self
+            .locks
+            .attempt_mut(|file| match file {
+                KaggFile::Data(data) => Ok(op(data.to_mut())),
+                _ => Err(FileSystemError::IncorrectFileType(
+                    "file is not a data file",
+                )),
+            })
+            .map_err(|_err| FileSystemError::IncorrectFileType("file is not a data file"))
+            .flatten()?;
+        self.changed();
+        Ok(result)
+    }
+
+    /// Marks this file as changed now
+    fn changed(&self) {
+        let size = self
+            .locks
+            .attempt(|file| file.size())
+            .or_else(|_| self.locks.attempt_dir(|dir| dir.len()))
+            .unwrap_or(0);
+        self.locks.info().touch(size);
+    }
+
+    /// Sets the modification time to now without changing anything, like `touch` does
+    pub fn touch(&mut self) -> Result<(), FileSystemError> {
+        self.check_writable()?;
+        self.changed();
+        Ok(())
+    }
+
+    /// Replaces the attributes of this file, which works on read-only files too
+    pub fn set_attributes(&mut self, attributes: Attributes) {
+        self.locks.info().set_attributes(attributes)
+    }
+
+    fn check_writable(&self) -> Result<(), FileSystemError> {
+        match self
+            .locks
+            .info()
+            .attributes()
+            .contains(Attributes::READ_ONLY)
+        {
+            true => Err(FileSystemError::ReadOnly),
+            false => Ok(()),
+        }
+    }
+
+    /// Add a new file inside this one if it's a directory, the file is given back if it can't be
+    pub fn add_child(&mut self, file: File) -> Result<(), (FileSystemError, File)> {
+        if let Err(err) = self.check_writable() {
+            return Err((err, file));
+        }
+        let mut file = Some(file);
+        match self
+            .locks
+            .attempt_dir_mut(|dir| dir.add_file(file.take().unwrap()))
+        {
+            Ok(Ok(())) => (),
+            Ok(Err(failed)) => return Err(failed),
+            Err(_err) => {
+                let error = FileSystemError::IncorrectFileType("file is not a directory");
+                return Err((error, file.unwrap()));
+            }
+        }
+        self.changed();
+        Ok(())
+    }
+
+    /// Remove a file inside this one if it's a directory
+    pub fn remove_child(&mut self, name: &str, recursive: bool) -> Result<(), FileSystemError> {
+        self.take_child_checked(name, recursive).map(|_| ())
+    }
+
+    /// Take a file out of this one if it's a directory, keeping what's in it
+    pub fn take_child(&mut self, name: &str) -> Result<File, FileSystemError> {
+        self.take_child_checked(name, true)
+    }
+
+    fn take_child_checked(&mut self, name: &str, recursive: bool) -> Result<File, FileSystemError> {
+        self.check_writable()?;
+        let file = self
+            .locks
+            .attempt_dir_mut(|dir| dir.take_file(name, recursive))
+            .map_err(|_err| FileSystemError::IncorrectFileType("file is not a directory"))
+            .flatten()?;
+        self.changed();
+        Ok(file)
+    }
+}
+
+impl<'a, T: FileHandlePriviliges> ErrorType for LittleFileHandle<'a, T> {
+    type Error = FileSystemError;
+}
+impl<'a, T: FileHandlePriviliges> Read for LittleFileHandle<'a, T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        LittleFileHandle::read(self, buf)
+    }
+}
+impl<'a, T: FileHandlePriviliges> Seek for LittleFileHandle<'a, T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64, Self::Error> {
+        LittleFileHandle::seek(self, pos)
+    }
+}
+impl<'a> Write for LittleFileHandle<'a, WritePriviliges> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        LittleFileHandle::write(self, buf)
+    }
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
     }
 }
 
@@ -140,18 +344,21 @@ impl<'a> LittleFileHandle<'a, ReadPriviliges> {
     pub fn new(
         path: PathString,
         filesystem: RwLockReadGuard<'a, Option<RwLock<Directory>>>,
+        wait: Wait,
     ) -> Result<Self, FileSystemError> {
         let path = path.clean();
         let locks = unsafe {
             FileHandleLocks::read(
                 filesystem.as_ref().ok_or(FileSystemError::PointerError)? as *const _,
                 &path,
+                wait,
             )?
         };
         Ok(Self {
             _filesystem: filesystem,
             locks,
             path,
+            position: 0,
             _phantom: PhantomData,
         })
     }
@@ -161,15 +368,21 @@ impl<'a> LittleFileHandle<'a, ReadPriviliges> {
 ///
 /// This is also where accesses and modifications of the handled file
 /// can be made in an abstracted fasion
+///
+/// Locks are always taken from the root directory down to the file, readers on every
+/// directory on the way and a writer only on the file itself, and they're held until the
+/// handle is dropped
 pub enum FileHandleLocks<'a> {
     Reading {
         root_directory: RwLockReadGuard<'a, Directory>,
         further_locks: Vec<RwLockReadGuard<'a, KaggFile>>,
+        info: &'a FileInfo,
     },
     Writing {
         root_directory: RwLockReadGuard<'a, Directory>,
         further_locks: Vec<RwLockReadGuard<'a, KaggFile>>,
         write_lock: RwLockWriteGuard<'a, KaggFile>,
+        info: &'a FileInfo,
     },
     WritingRoot {
         root_directory: RwLockWriteGuard<'a, Directory>,
@@ -181,6 +394,13 @@ pub enum ManiupulationError {
     TryingToWriteOnRead,
 }
 impl<'a> FileHandleLocks<'a> {
+    fn info(&self) -> &'a FileInfo {
+        match self {
+            FileHandleLocks::Reading { info, .. } => info,
+            FileHandleLocks::Writing { info, .. } => info,
+            FileHandleLocks::WritingRoot { .. } => &ROOT_INFO,
+        }
+    }
     fn attempt<R>(&self, op: impl FnOnce(&KaggFile) -> R) -> Result<R, ManiupulationError> {
         match self {
             FileHandleLocks::Reading { further_locks, .. } => Ok(op(&**further_locks
@@ -235,96 +455,77 @@ impl<'a> FileHandleLocks<'a> {
     unsafe fn read(
         filesystem: *const RwLock<Directory>,
         path: &PathString,
+        wait: Wait,
     ) -> Result<Self, FileSystemError> {
         let segments = path.components();
-        let root_directory = filesystem.as_ref().unwrap().read();
+        let filesystem = filesystem.as_ref().ok_or(FileSystemError::PointerError)?;
+        let root_directory = wait.acquire(|| filesystem.try_read())?;
         let mut further_locks: Vec<RwLockReadGuard<'_, KaggFile>> = Vec::new();
+        let mut info = &ROOT_INFO;
         for section in segments {
             if section == "" {
                 continue;
             }
-            let new_lock = if let Some(lock) = further_locks.last() {
-                let guard = match &**lock {
-                    KaggFile::Directory(directory) => (directory as *const Directory)
-                        .as_ref()
-                        .ok_or(FileSystemError::PointerError)?
-                        .fetch(section),
-                    _ => None,
-                };
-                guard
-            } else {
-                (&*root_directory as *const Directory)
-                    .as_ref()
-                    .ok_or(FileSystemError::PointerError)?
-                    .fetch(section)
-            };
-            further_locks.push(new_lock.ok_or(FileSystemError::FileNotFound("case 1"))?);
+            let directory = Self::innermost_directory(&root_directory, &further_locks)?;
+            further_locks.push(directory.try_fetch(section, wait)?);
+            info = directory
+                .info(section)
+                .ok_or(FileSystemError::PointerError)?;
         }
         Ok(Self::Reading {
             root_directory,
             further_locks,
+            info,
         })
     }
-    unsafe fn write_root(filesystem: *const RwLock<Directory>) -> Result<Self, FileSystemError> {
+    unsafe fn write_root(
+        filesystem: *const RwLock<Directory>,
+        wait: Wait,
+    ) -> Result<Self, FileSystemError> {
+        let filesystem = filesystem.as_ref().ok_or(FileSystemError::PointerError)?;
         Ok(Self::WritingRoot {
-            root_directory: filesystem
-                .as_ref()
-                .ok_or(FileSystemError::PointerError)?
-                .write(),
+            root_directory: wait.acquire(|| filesystem.try_write())?,
         })
     }
     unsafe fn write(
         filesystem: *const RwLock<Directory>,
         path: &PathString,
+        wait: Wait,
     ) -> Result<Self, FileSystemError> {
         let segments: Vec<_> = path.components().collect();
-        let root_directory = filesystem
-            .as_ref()
-            .ok_or(FileSystemError::PointerError)?
-            .read();
+        let filesystem = filesystem.as_ref().ok_or(FileSystemError::PointerError)?;
+        let root_directory = wait.acquire(|| filesystem.try_read())?;
         let mut further_locks: Vec<RwLockReadGuard<'_, KaggFile>> = Vec::new();
         for section in &segments[0..segments.len() - 1] {
             if section == &"" {
                 continue;
             }
-            let new_lock = if let Some(lock) = further_locks.last() {
-                let guard = match &**lock {
-                    KaggFile::Directory(directory) => (directory as *const Directory)
-                        .as_ref()
-                        .ok_or(FileSystemError::PointerError)?
-                        .fetch(section),
-                    _ => None,
-                };
-                guard
-            } else {
-                (&*root_directory as *const Directory)
-                    .as_ref()
-                    .ok_or(FileSystemError::PointerError)?
-                    .fetch(section)
-            };
-            further_locks.push(new_lock.ok_or(FileSystemError::FileNotFound("case 2"))?);
+            let directory = Self::innermost_directory(&root_directory, &further_locks)?;
+            further_locks.push(directory.try_fetch(section, wait)?);
         }
         let file_name = segments.last().unwrap();
-        let write_lock = if let Some(lock) = further_locks.last() {
-            let guard = match &**lock {
-                KaggFile::Directory(directory) => (directory as *const Directory)
-                    .as_ref()
-                    .ok_or(FileSystemError::PointerError)?
-                    .fetch_write(file_name),
-                _ => None,
-            };
-            guard
-        } else {
-            (&*root_directory as *const Directory)
-                .as_ref()
-                .ok_or(FileSystemError::PointerError)?
-                .fetch_write(file_name)
-        }
-        .ok_or(FileSystemError::FileNotFound("case 3"))?;
+        let directory = Self::innermost_directory(&root_directory, &further_locks)?;
+        let write_lock = directory.try_fetch_write(file_name, wait)?;
+        let info = directory
+            .info(file_name)
+            .ok_or(FileSystemError::PointerError)?;
         Ok(Self::Writing {
             root_directory,
             further_locks,
             write_lock,
+            info,
         })
     }
+    /// The directory which the last lock is on, which lives as long as the locks
+    unsafe fn innermost_directory(
+        root_directory: &RwLockReadGuard<'a, Directory>,
+        further_locks: &[RwLockReadGuard<'a, KaggFile>],
+    ) -> Result<&'a Directory, FileSystemError> {
+        let directory = match further_locks.last().map(|lock| &**lock) {
+            Some(KaggFile::Directory(directory)) => directory as *const Directory,
+            Some(_) => return Err(FileSystemError::FileNotFound("a file is not a directory")),
+            None => &**root_directory as *const Directory,
+        };
+        directory.as_ref().ok_or(FileSystemError::PointerError)
+    }
 }
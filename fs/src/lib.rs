@@ -6,13 +6,22 @@ extern crate alloc;
 pub mod apps;
 mod directory;
 mod file;
+mod lock;
+mod metadata;
 mod path;
+mod tar;
+mod walk;
 pub use apps::{DefaultInstall, InstallableApp};
 use base::debug;
 pub use directory::*;
 pub use file::*;
+pub use lock::*;
+pub use metadata::*;
 use handle::{LittleFileHandle, ReadPriviliges, WritePriviliges};
 pub use path::*;
+pub use tar::*;
+pub use walk::*;
+pub use embedded_io::SeekFrom;
 
 use alloc::{
     boxed::Box,
@@ -22,6 +31,7 @@ use alloc::{
 
 pub mod handle;
 
+use embedded_io::ErrorKind;
 use spin::RwLock;
 pub struct RamFileSystem(RwLock<Option<RwLock<Directory>>>);
 
@@ -36,6 +46,52 @@ pub enum FileSystemError {
     EmptyPath,
     PointerError,
     NameAlreadyExists,
+    DirectoryNotEmpty,
+    ReadOnly,
+    InvalidSeek,
+    InvalidName,
+    Interrupted,
+}
+impl FileSystemError {
+    /// The status code `run` pushes when an app can't be started because of this error
+    ///
+    /// Like [base::ProgramError::code], but from 16 and up
+    pub fn code(&self) -> isize {
+        match self {
+            Self::FileSystemNotInitialized => 16,
+            Self::IncorrectFileType(_) => 17,
+            Self::FileNotFound(_) => 18,
+            Self::DirectoryNotFound => 19,
+            Self::InvalidParentDirectory => 20,
+            Self::Busy => 21,
+            Self::EmptyPath => 22,
+            Self::PointerError => 23,
+            Self::NameAlreadyExists => 24,
+            Self::DirectoryNotEmpty => 25,
+            Self::ReadOnly => 26,
+            Self::InvalidSeek => 27,
+            Self::InvalidName => 28,
+            Self::Interrupted => 29,
+        }
+    }
+}
+impl embedded_io::Error for FileSystemError {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::FileNotFound(_) | Self::DirectoryNotFound => ErrorKind::NotFound,
+            Self::NameAlreadyExists => ErrorKind::AlreadyExists,
+            Self::ReadOnly => ErrorKind::PermissionDenied,
+            Self::IncorrectFileType(_) => ErrorKind::Unsupported,
+            Self::Interrupted => ErrorKind::Interrupted,
+            Self::InvalidParentDirectory
+            | Self::EmptyPath
+            | Self::InvalidSeek
+            | Self::InvalidName => {
+                ErrorKind::InvalidInput
+            }
+            _ => ErrorKind::Other,
+        }
+    }
 }
 
 static FILE_SYSTEM: RamFileSystem = RamFileSystem(RwLock::new(None));
@@ -49,19 +105,22 @@ impl RamFileSystem {
     fn get_file<'b, P: AsRef<Path>>(
         &'b self,
         path: P,
+        wait: Wait,
     ) -> Result<LittleFileHandle<'b, ReadPriviliges>, FileSystemError> {
-        LittleFileHandle::<ReadPriviliges>::new(path.as_ref().to_pathstring().clean(), FILE_SYSTEM.0.read())
+        LittleFileHandle::<ReadPriviliges>::new(path.as_ref().to_pathstring().clean(), FILE_SYSTEM.0.read(), wait)
     }
     fn get_file_write<'b, P: AsRef<Path>>(
         &'b self,
         path: P,
+        wait: Wait,
     ) -> Result<LittleFileHandle<'b, WritePriviliges>, FileSystemError> {
-        LittleFileHandle::<WritePriviliges>::new(path.as_ref().to_pathstring().clean())
+        LittleFileHandle::<WritePriviliges>::new(path.as_ref().to_pathstring().clean(), wait)
     }
 }
 pub struct File {
     data: KaggFile,
     name: String,
+    info: FileInfo,
 }
 
 pub fn start() {
@@ -71,17 +130,31 @@ pub fn start() {
 pub fn get_file<P: AsRef<Path>>(
     path: P,
 ) -> Result<LittleFileHandle<'static, ReadPriviliges>, FileSystemError> {
-    FILE_SYSTEM.get_file(path)
+    FILE_SYSTEM.get_file(path, Wait::Never)
 }
 pub fn get_file_write<P: AsRef<Path>>(
     path: P,
 ) -> Result<LittleFileHandle<'static, WritePriviliges>, FileSystemError> {
-    FILE_SYSTEM.get_file_write(path)
+    FILE_SYSTEM.get_file_write(path, Wait::Never)
+}
+/// Like [get_file], but waits for other handles to let go of the file as long as `wait` says
+pub fn get_file_waiting<P: AsRef<Path>>(
+    path: P,
+    wait: Wait,
+) -> Result<LittleFileHandle<'static, ReadPriviliges>, FileSystemError> {
+    FILE_SYSTEM.get_file(path, wait)
+}
+/// Like [get_file_write], but waits for other handles to let go of the file as long as `wait` says
+pub fn get_file_write_waiting<P: AsRef<Path>>(
+    path: P,
+    wait: Wait,
+) -> Result<LittleFileHandle<'static, WritePriviliges>, FileSystemError> {
+    FILE_SYSTEM.get_file_write(path, wait)
 }
 pub fn get_file_relative<P: AsRef<PathString>>(
     path: P,
 ) -> Result<LittleFileHandle<'static, ReadPriviliges>, FileSystemError> {
-    FILE_SYSTEM.get_file(active_directory().append(path.as_ref()))
+    FILE_SYSTEM.get_file(active_directory().append(path.as_ref()), Wait::Never)
 }
 pub fn create_data_file<P: AsRef<Path>>(
     path: P,
@@ -101,6 +174,133 @@ pub fn install_app<A: InstallableApp>() -> Result<(), FileSystemError> {
     Ok(())
 }
 
+/// Removes a file, directories have to be empty unless `recursive` is set
+pub fn remove<P: AsRef<Path>>(path: P, recursive: bool) -> Result<(), FileSystemError> {
+    let (parent, name) = split_path(path)?;
+    get_file_write(parent)?.remove_child(&name, recursive)
+}
+
+/// Gives a file a new path, which may be in another directory
+pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<(), FileSystemError> {
+    let from = from.as_ref().to_pathstring().clean();
+    let to = to.as_ref().to_pathstring().clean();
+    if to.strip_prefix(&from).is_some_and(|rest| !rest.as_str().is_empty()) {
+        return Err(FileSystemError::InvalidParentDirectory);
+    }
+    if exists(&to) {
+        return Err(FileSystemError::NameAlreadyExists);
+    }
+    let (from_parent, from_name) = split_path(&from)?;
+    let (to_parent, to_name) = split_path(&to)?;
+    // Everything which can be checked is checked before the file is taken out
+    if !is_valid_name(&to_name) {
+        return Err(FileSystemError::InvalidName);
+    }
+    let destination = get_file(&to_parent)?;
+    if !destination.is_directory() {
+        return Err(FileSystemError::InvalidParentDirectory);
+    }
+    if destination.stat().attributes.contains(Attributes::READ_ONLY) {
+        return Err(FileSystemError::ReadOnly);
+    }
+    drop(destination);
+    // The file is taken out before it's added again, since a handle to the
+    // destination may not be open together with one to the source
+    let File { data, info, .. } = get_file_write(from_parent.clone())?.take_child(&from_name)?;
+    let file = File {
+        data,
+        name: to_name,
+        info,
+    };
+    let (err, file) = match get_file_write(to_parent) {
+        Ok(mut destination) => match destination.add_child(file) {
+            Ok(()) => return Ok(()),
+            Err(failed) => failed,
+        },
+        Err(err) => (err, file),
+    };
+    // It goes back where it was, which nothing else can have taken in between
+    get_file_write(from_parent)?
+        .add_child(File {
+            name: from_name,
+            ..file
+        })
+        .map_err(|(err, _)| err)?;
+    Err(err)
+}
+
+/// Moves a file into a directory, keeping its name
+pub fn move_to<P: AsRef<Path>, Q: AsRef<Path>>(path: P, directory: Q) -> Result<(), FileSystemError> {
+    let (_, name) = split_path(&path)?;
+    let to = directory.as_ref().to_pathstring().append(&PathString(name));
+    rename(path, to)
+}
+
+/// Copies a data file, or a directory and everything in it
+///
+/// Apps can't be copied
+pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<(), FileSystemError> {
+    copy_until(from, to, &mut || false)
+}
+
+/// Like [copy], but asks `interrupted` before each file and stops with `Interrupted` once it says so
+///
+/// What was copied before that is kept
+pub fn copy_until<P: AsRef<Path>, Q: AsRef<Path>>(
+    from: P,
+    to: Q,
+    interrupted: &mut dyn FnMut() -> bool,
+) -> Result<(), FileSystemError> {
+    if interrupted() {
+        return Err(FileSystemError::Interrupted);
+    }
+    let from = from.as_ref().to_pathstring().clean();
+    let to = to.as_ref().to_pathstring().clean();
+    if to.strip_prefix(&from).is_some_and(|rest| !rest.as_str().is_empty()) {
+        return Err(FileSystemError::InvalidParentDirectory);
+    }
+    if exists(&to) {
+        return Err(FileSystemError::NameAlreadyExists);
+    }
+    let source = get_file(&from)?;
+    if source.is_directory() {
+        let items = source.read_dir()?;
+        drop(source);
+        create_dir(&to)?;
+        for item in items.items() {
+            copy_until(
+                from.clone().append(&item.path),
+                to.clone().append(&item.path),
+                interrupted,
+            )?;
+        }
+        Ok(())
+    } else {
+        let data = source.read_file()?;
+        drop(source);
+        create_data_file(&to, data).map(|_| ())
+    }
+}
+
+/// Whether there is a file at the path, files which another handle has locked count as well
+fn exists<P: AsRef<Path>>(path: P) -> bool {
+    matches!(get_file(path), Ok(_) | Err(FileSystemError::Busy))
+}
+
+/// Splits a path into the directory it's in and its name
+fn split_path<P: AsRef<Path>>(path: P) -> Result<(PathString, String), FileSystemError> {
+    let mut parent = path.as_ref().to_pathstring().clean();
+    match parent.pop() {
+        Some(name) if !name.as_str().is_empty() => Ok((parent, name.0)),
+        _ => Err(FileSystemError::EmptyPath),
+    }
+}
+
+/// The size, times and attributes of a file
+pub fn stat<P: AsRef<Path>>(path: P) -> Result<FileMetadata, FileSystemError> {
+    Ok(get_file(path)?.stat())
+}
+
 pub fn read_dir<P: AsRef<Path>>(path: P) -> Result<DirRead, FileSystemError> {
     match get_file(path) {
         Ok(file_handle) => file_handle.read_dir(),
@@ -111,7 +311,27 @@ pub fn read_dir<P: AsRef<Path>>(path: P) -> Result<DirRead, FileSystemError> {
 pub struct FileMetadata {
     pub path: PathString,
     pub filetype: FileType,
+    /// Bytes in a data file, files in a directory and 0 for an app
+    pub size: usize,
+    /// Seconds since the system started when the file was created
+    pub created: u64,
+    /// Seconds since the system started when the file was last written to
+    pub modified: u64,
+    pub attributes: Attributes,
+}
+impl FileMetadata {
+    pub(crate) fn new(path: PathString, filetype: FileType, info: &FileInfo) -> Self {
+        Self {
+            path,
+            filetype,
+            size: info.size(),
+            created: info.created(),
+            modified: info.modified(),
+            attributes: info.attributes(),
+        }
+    }
 }
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum FileType {
     Directory,
     Data,
@@ -119,24 +339,27 @@ pub enum FileType {
 }
 
 impl File {
+    /// A new file created now, apps are executable
+    pub(crate) fn new(data: KaggFile, name: String) -> Self {
+        let attributes = match data {
+            KaggFile::App(_) => Attributes::EXECUTABLE,
+            _ => Attributes::NONE,
+        };
+        let info = FileInfo::new(attributes, data.size());
+        Self { data, name, info }
+    }
     pub fn empty<S: ToString>(name: S) -> Self {
-        Self {
-            data: KaggFile::Data(Vec::new().into()),
-            name: name.to_string(),
-        }
+        Self::new(KaggFile::Data(Vec::new().into()), name.to_string())
     }
     pub fn from_app<S: ToString>(app: Box<dyn AppConstructor>, name: S) -> Self {
-        Self {
-            data: KaggFile::App(app),
-            name: name.to_string(),
-        }
+        Self::new(KaggFile::App(app), name.to_string())
     }
 }
 pub fn active_directory() -> PathString {
     unsafe {
         ACTIVE_DIRECTORY
             .as_ref()
-            .unwrap_or(&PathString(String::from("")))
+            .unwrap_or(&PathString(String::from("/")))
             .clone()
     }
 }
@@ -150,15 +373,9 @@ pub(crate) fn create_file<P: AsRef<Path>>(
 ) -> Result<LittleFileHandle<'static, WritePriviliges>, FileSystemError> {
     let mut parent = path.as_ref().to_pathstring();
     let file_name = parent.pop().unwrap();
-    match FILE_SYSTEM.get_file_write(parent) {
-        Ok(mut exists) => {
-            exists.add_child(File {
-                data: file,
-                name: file_name.0,
-            })?;
-        }
-        Err(_) => return Err(FileSystemError::Busy),
-    }
+    get_file_write(parent)?
+        .add_child(File::new(file, file_name.0))
+        .map_err(|(err, _)| err)?;
 
-    FILE_SYSTEM.get_file_write(path)
+    get_file_write(path)
 }
@@ -0,0 +1,37 @@
+use crate::{now, FileSystemError};
+
+/// How long opening a file waits for other handles to let go of it before failing with `Busy`
+///
+/// Handles lock every directory from the root down to the file in that order and operations
+/// on several files never keep more than one handle open at a time, so two handles can't wait
+/// for each other. Time is measured by the file system clock, see [crate::CLOCK]
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Wait {
+    /// Fail right away
+    #[default]
+    Never,
+    /// Wait up to this many seconds
+    Seconds(u64),
+}
+
+impl Wait {
+    /// Tries to take a lock until it's taken or the time is up
+    pub(crate) fn acquire<G>(
+        self,
+        mut attempt: impl FnMut() -> Option<G>,
+    ) -> Result<G, FileSystemError> {
+        let deadline = match self {
+            Wait::Never => return attempt().ok_or(FileSystemError::Busy),
+            Wait::Seconds(seconds) => now() + seconds,
+        };
+        loop {
+            if let Some(guard) = attempt() {
+                return Ok(guard);
+            }
+            if now() > deadline {
+                return Err(FileSystemError::Busy);
+            }
+            core::hint::spin_loop();
+        }
+    }
+}
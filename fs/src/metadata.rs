@@ -0,0 +1,95 @@
+use core::{
+    ops::BitOr,
+    sync::atomic::{AtomicU64, AtomicU8, AtomicUsize, Ordering},
+};
+
+/// Tells the file system the time in seconds since the system started, set by the kernel
+///
+/// Files get the time 0 until it's set
+pub static mut CLOCK: Option<fn() -> u64> = None;
+
+pub(crate) fn now() -> u64 {
+    unsafe { CLOCK }.map(|clock| clock()).unwrap_or(0)
+}
+
+/// The info of the root directory, which isn't in any directory
+pub(crate) static ROOT_INFO: FileInfo = FileInfo {
+    created: 0,
+    modified: AtomicU64::new(0),
+    attributes: AtomicU8::new(0),
+    size: AtomicUsize::new(0),
+};
+
+/// Attributes of a file, combined with `|`
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Attributes(u8);
+
+impl BitOr for Attributes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl Attributes {
+    pub const NONE: Self = Self(0);
+    /// The file can't be written to, removed or moved
+    pub const READ_ONLY: Self = Self(0b001);
+    /// The file isn't listed unless asked for
+    pub const HIDDEN: Self = Self(0b010);
+    /// The file is an app which can be run
+    pub const EXECUTABLE: Self = Self(0b100);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub fn without(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+/// When a file was created and last changed, its attributes and size
+///
+/// It's kept outside the file's lock so it can be read and updated while a handle has the file
+pub struct FileInfo {
+    created: u64,
+    modified: AtomicU64,
+    attributes: AtomicU8,
+    size: AtomicUsize,
+}
+
+impl FileInfo {
+    pub(crate) fn new(attributes: Attributes, size: usize) -> Self {
+        let now = now();
+        Self {
+            created: now,
+            modified: AtomicU64::new(now),
+            attributes: AtomicU8::new(attributes.0),
+            size: AtomicUsize::new(size),
+        }
+    }
+    /// Seconds since the system started when the file was created
+    pub fn created(&self) -> u64 {
+        self.created
+    }
+    /// Seconds since the system started when the file was last written to
+    pub fn modified(&self) -> u64 {
+        self.modified.load(Ordering::Relaxed)
+    }
+    /// Bytes in a data file, files in a directory and 0 for an app
+    pub fn size(&self) -> usize {
+        self.size.load(Ordering::Relaxed)
+    }
+    pub fn attributes(&self) -> Attributes {
+        Attributes(self.attributes.load(Ordering::Relaxed))
+    }
+    pub(crate) fn set_attributes(&self, attributes: Attributes) {
+        self.attributes.store(attributes.0, Ordering::Relaxed)
+    }
+    /// Marks the file as changed now, with its new size
+    pub(crate) fn touch(&self, size: usize) {
+        self.modified.store(now(), Ordering::Relaxed);
+        self.size.store(size, Ordering::Relaxed);
+    }
+}
@@ -29,18 +29,57 @@ impl AsRef<Path> for PathString {
 }
 
 impl Path {
+    /// The parts between the slashes, the root is a single empty one
     pub fn components(&self) -> impl Iterator<Item = &str> {
-        self.0.split("/")
+        match &self.0 {
+            "/" => "",
+            path => path,
+        }
+        .split("/")
+    }
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+    /// Whether the path starts at the root, relative paths start wherever they're joined
+    pub fn is_absolute(&self) -> bool {
+        self.0.starts_with('/')
     }
+    pub fn is_relative(&self) -> bool {
+        !self.is_absolute()
+    }
+    /// What's after the last dot of the name, names without a dot or only
+    /// starting with one like `.hidden` have no extension
     pub fn file_extension(&self) -> Option<&str> {
-        self.components()
-            .last()
-            .map(|i| i.split('.').last())
-            .flatten()
+        let name = &self.file_name()?.0;
+        let (stem, extension) = name.rsplit_once('.')?;
+        (!stem.is_empty()).then_some(extension)
+    }
+    /// The name without its extension
+    pub fn file_stem(&self) -> Option<&str> {
+        let name = &self.file_name()?.0;
+        match name.rsplit_once('.') {
+            Some((stem, _)) if !stem.is_empty() => Some(stem),
+            _ => Some(name),
+        }
     }
     pub fn file_name(&self) -> Option<&Self> {
         self.components().last().map(|i| Self::new(i))
     }
+    /// The rest of the path after `prefix`, which has to be whole components
+    ///
+    /// `/bin/app.run` without `/bin` is `app.run`, without `/bi` it's nothing.
+    /// An empty prefix leaves the whole path
+    pub fn strip_prefix<P: AsRef<Path> + ?Sized>(&self, prefix: &P) -> Option<&Path> {
+        if prefix.as_ref().0.is_empty() {
+            return Some(self);
+        }
+        let prefix = prefix.as_ref().0.trim_end_matches('/');
+        let rest = self.0.strip_prefix(prefix)?;
+        match rest.is_empty() {
+            true => Some(Self::new(rest)),
+            false => rest.strip_prefix('/').map(Self::new),
+        }
+    }
     pub fn to_pathstring(&self) -> PathString {
         PathString::from(self)
     }
@@ -85,12 +124,16 @@ impl PathString {
         *self = Self::from_segments(&segments[..segments.len() - 1]);
         Some(Self::from(*segments.last()?))
     }
+    /// The absolute path through `items`, no items is the root
     pub fn from_segments(items: &[&str]) -> Self {
-        Self(items.iter().fold(String::new(), |mut x, i| {
-            x.push('/');
-            x.push_str(i);
-            x
-        }))
+        match items.is_empty() {
+            true => Self::from("/"),
+            false => Self(items.iter().fold(String::new(), |mut x, i| {
+                x.push('/');
+                x.push_str(i);
+                x
+            })),
+        }
     }
     pub fn new() -> Self {
         Self(String::new())
@@ -98,12 +141,54 @@ impl PathString {
     pub fn as_str(&self) -> &str {
         self.0.as_str()
     }
+    /// Adds `path` after this one, even when it's absolute, see [Self::join]
     pub fn append<A: AppendsPath>(mut self, path: &A) -> Self {
-        self.0.push_str("/");
+        if !self.0.ends_with('/') {
+            self.0.push('/');
+        }
         self.0.push_str(path.to_str());
         self
     }
+    /// Adds `path` after this one if it's relative, an absolute `path` replaces this one
+    pub fn join<A: AppendsPath>(self, path: &A) -> Self {
+        match Path::new(path.to_str()).is_absolute() {
+            true => Self::from(path.to_str()),
+            false => self.append(path),
+        }
+    }
+    /// Replaces `~` at the start of the path with `home`
+    pub fn expand_home<A: AppendsPath>(self, home: &A) -> Self {
+        match self.0.strip_prefix('~') {
+            Some("") => Self::from(home.to_str()),
+            Some(rest) if rest.starts_with('/') => Self::from(home.to_str()).append(&&rest[1..]),
+            _ => self,
+        }
+    }
+    /// How to get to this path from the directory `base`, with `..` for every
+    /// directory to go up first
+    ///
+    /// Both are cleaned first, so relative paths are taken from the root
+    pub fn relative_to<A: AppendsPath>(&self, base: &A) -> Self {
+        let path = self.clone().clean();
+        let base = Self::from(base.to_str()).clean();
+        let path: Vec<_> = path.components().skip(1).collect();
+        let base: Vec<_> = base.components().skip(1).collect();
+        let common = path
+            .iter()
+            .zip(base.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut relative = Vec::new();
+        relative.extend(base[common..].iter().map(|_| ".."));
+        relative.extend(path[common..].iter().copied());
+        match relative.is_empty() {
+            true => Self::from("."),
+            false => Self(relative.join("/")),
+        }
+    }
 
+    /// Removes `.`, `..` and empty components, the result is always absolute
+    /// so relative paths are taken from the root, which is `/`
     pub fn clean(self) -> Self {
         let mut new = Vec::new();
         let sections = self.0.split('/');
@@ -124,13 +209,33 @@ impl PathString {
             finale.push('/');
             finale.push_str(part);
         }
-        Self(finale)
+        match finale.is_empty() {
+            true => Self::from("/"),
+            false => Self(finale),
+        }
     }
     pub fn add_extension(mut self, extension: &str) -> Self {
         self.0.push('.');
         self.0.push_str(extension);
         self
     }
+    /// Replaces the extension of the name, an empty `extension` removes it
+    pub fn with_extension(mut self, extension: &str) -> Self {
+        if let Some(old) = self.file_extension() {
+            let length = self.0.len() - old.len() - 1;
+            self.0.truncate(length);
+        }
+        match extension.is_empty() {
+            true => self,
+            false => self.add_extension(extension),
+        }
+    }
+}
+
+/// Whether a file can have this name, which can't be empty, `.` or `..`, or
+/// contain `/` or the wildcards `*` and `?`
+pub fn is_valid_name(name: &str) -> bool {
+    !matches!(name, "" | "." | "..") && !name.contains(['/', '*', '?', '\0'])
 }
 pub trait AppendsPath {
     fn to_str(&self) -> &str;
@@ -159,4 +264,4 @@ impl AsRef<Path> for String {
     fn as_ref(&self) -> &Path {
         Path::new(self)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,80 @@
+use core::str::from_utf8;
+
+use alloc::{format, string::String};
+
+use crate::{create_data_file, create_dir, get_file, get_file_write, FileSystemError, PathString};
+
+const BLOCK_SIZE: usize = 512;
+
+/// Unpacks a ustar archive into the directory at `path`, returns how many files were unpacked
+///
+/// Directories are created as needed and files which already exist are replaced. Only regular
+/// files and directories are unpacked, links and other entries are skipped. New files are read
+/// straight from the archive without being copied, so it has to stay in memory
+pub fn unpack_tar(archive: &'static [u8], path: PathString) -> Result<usize, FileSystemError> {
+    let mut unpacked = 0;
+    let mut offset = 0;
+    while let Some(header) = archive.get(offset..offset + BLOCK_SIZE) {
+        // The archive ends with empty blocks
+        if header[0] == 0 {
+            break;
+        }
+        if &header[257..262] != b"ustar" {
+            return Err(FileSystemError::IncorrectFileType("not a tar archive"));
+        }
+        let size = octal(&header[124..136])?;
+        let data_start = offset + BLOCK_SIZE;
+        let data = archive.get(data_start..data_start + size).ok_or(
+            FileSystemError::IncorrectFileType("tar archive is cut short"),
+        )?;
+        offset = data_start + (size + BLOCK_SIZE - 1) / BLOCK_SIZE * BLOCK_SIZE;
+
+        let name = match (text(&header[345..500])?, text(&header[0..100])?) {
+            ("", name) => String::from(name),
+            (prefix, name) => format!("{prefix}/{name}"),
+        };
+        let file_path = path.clone().append(&name).clean();
+        match header[156] {
+            b'5' => create_directories(&file_path)?,
+            b'0' | 0 => {
+                if let Some(parent) = file_path.parent() {
+                    create_directories(&parent)?;
+                }
+                match get_file_write(&file_path) {
+                    Ok(mut file) => file.write_file(data)?,
+                    Err(_) => create_data_file(&file_path, data).map(|_| ())?,
+                }
+                unpacked += 1;
+            }
+            _ => continue,
+        }
+    }
+    Ok(unpacked)
+}
+
+/// Creates the directory and the ones it's in unless they exist
+fn create_directories(path: &PathString) -> Result<(), FileSystemError> {
+    if get_file(path).is_ok() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        create_directories(&parent)?;
+    }
+    create_dir(path).map(|_| ())
+}
+
+/// A null terminated text field of a header
+fn text(field: &[u8]) -> Result<&str, FileSystemError> {
+    let end = field.iter().position(|c| *c == 0).unwrap_or(field.len());
+    from_utf8(&field[..end]).map_err(|_| FileSystemError::IncorrectFileType("tar name isn't text"))
+}
+
+/// A number field of a header, written in octal
+fn octal(field: &[u8]) -> Result<usize, FileSystemError> {
+    let digits = text(field)?.trim();
+    match digits.is_empty() {
+        true => Ok(0),
+        false => usize::from_str_radix(digits, 8)
+            .map_err(|_| FileSystemError::IncorrectFileType("tar size isn't a number")),
+    }
+}
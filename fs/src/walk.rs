@@ -0,0 +1,116 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{active_directory, read_dir, stat, FileMetadata, FileType, Path, PathString};
+
+/// Goes through everything below a directory, depth first and sorted by name
+///
+/// Each directory is read when the walk gets to it, so nothing is locked in between
+/// and directories which are busy are left out
+pub struct Walk {
+    stack: Vec<FileMetadata>,
+}
+
+impl Walk {
+    fn push_children(&mut self, directory: &PathString) {
+        let Ok(items) = read_dir(directory) else {
+            return;
+        };
+        let mut items: Vec<_> = items
+            .items()
+            .map(|item| FileMetadata {
+                path: directory.clone().append(&item.path).clean(),
+                ..item
+            })
+            .collect();
+        items.sort_by(|a, b| b.path.as_str().cmp(a.path.as_str()));
+        self.stack.extend(items);
+    }
+}
+
+impl Iterator for Walk {
+    type Item = FileMetadata;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.stack.pop()?;
+        if item.filetype == FileType::Directory {
+            self.push_children(&item.path);
+        }
+        Some(item)
+    }
+}
+
+/// Everything below the directory at `path` with full paths, see [Walk]
+pub fn walk<P: AsRef<Path>>(path: P) -> Walk {
+    let mut walk = Walk { stack: Vec::new() };
+    walk.push_children(&path.as_ref().to_pathstring().clean());
+    walk
+}
+
+/// The files matching a pattern, relative patterns are in the active directory
+///
+/// - `*` is any part of a name and `?` is any one character
+/// - `**` is any number of directories, so `bin/**/*.run` is every app in or below bin
+pub fn glob(pattern: &str) -> impl Iterator<Item = FileMetadata> {
+    let pattern = match pattern.starts_with('/') {
+        true => PathString::from(pattern),
+        false => active_directory().append(&pattern),
+    }
+    .clean();
+    let components: Vec<String> = pattern.components().skip(1).map(String::from).collect();
+    let literal = components
+        .iter()
+        .take_while(|component| !component.contains(['*', '?']))
+        .count();
+    let base = PathString::from_segments(
+        &components[..literal]
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+    );
+    // Without wildcards there's at most the file itself
+    let single = match literal == components.len() {
+        true => stat(&base).ok(),
+        false => None,
+    };
+    let walked = match literal < components.len() {
+        true => Some(walk(&base)),
+        false => None,
+    };
+    single
+        .into_iter()
+        .chain(walked.into_iter().flatten())
+        .filter(move |item| {
+            let path: Vec<_> = item.path.components().skip(1).collect();
+            let pattern: Vec<_> = components.iter().map(String::as_str).collect();
+            matches_components(&pattern, &path)
+        })
+}
+
+/// Whether `name` matches a pattern of one path component, with `*` and `?`
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let (pattern, name): (Vec<_>, Vec<_>) = (pattern.chars().collect(), name.chars().collect());
+    matches_chars(&pattern, &name)
+}
+
+fn matches_chars(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skipped| matches_chars(rest, &name[skipped..])),
+        Some(('?', rest)) => !name.is_empty() && matches_chars(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_chars(rest, &name[1..]),
+    }
+}
+
+fn matches_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => {
+            (0..=path.len()).any(|skipped| matches_components(rest, &path[skipped..]))
+        }
+        Some((component, rest)) => {
+            !path.is_empty()
+                && glob_match(component, path[0])
+                && matches_components(rest, &path[1..])
+        }
+    }
+}
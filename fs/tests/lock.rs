@@ -0,0 +1,65 @@
+use std::{
+    sync::{mpsc, Once, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+use fs::{FileSystemError, Wait};
+
+static START: Once = Once::new();
+static STARTED_AT: OnceLock<Instant> = OnceLock::new();
+
+fn seconds() -> u64 {
+    STARTED_AT.get_or_init(Instant::now).elapsed().as_secs()
+}
+
+/// Every test shares the file system, so each one has a file of its own
+fn file(name: &str) -> String {
+    START.call_once(|| {
+        fs::start();
+        unsafe { fs::CLOCK = Some(seconds) };
+    });
+    let path = format!("/{name}.txt");
+    fs::create_data_file(&path, b"hej".to_vec()).unwrap();
+    path
+}
+
+/// Keeps the file open for writing on another thread until told to let go
+fn hold(path: &str) -> (mpsc::Sender<()>, thread::JoinHandle<()>) {
+    let (locked, is_locked) = mpsc::channel();
+    let (release, released) = mpsc::channel();
+    let path = String::from(path);
+    let holder = thread::spawn(move || {
+        let handle = fs::get_file_write(&path).unwrap();
+        locked.send(()).unwrap();
+        released.recv().unwrap();
+        drop(handle);
+    });
+    is_locked.recv().unwrap();
+    (release, holder)
+}
+
+#[test]
+fn waiting_gets_the_file_once_it_is_let_go() {
+    let path = file("let_go");
+    let (release, holder) = hold(&path);
+    assert!(matches!(fs::get_file(&path), Err(FileSystemError::Busy)));
+    let releaser = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        release.send(()).unwrap();
+    });
+    assert!(fs::get_file_waiting(&path, Wait::Seconds(5)).is_ok());
+    releaser.join().unwrap();
+    holder.join().unwrap();
+}
+
+#[test]
+fn waiting_gives_up_when_the_time_is_up() {
+    let path = file("time_is_up");
+    let (release, holder) = hold(&path);
+    let result = fs::get_file_write_waiting(&path, Wait::Seconds(1));
+    assert!(matches!(result, Err(FileSystemError::Busy)));
+    release.send(()).unwrap();
+    holder.join().unwrap();
+    assert!(fs::get_file_write_waiting(&path, Wait::Seconds(1)).is_ok());
+}
@@ -0,0 +1,103 @@
+use fs::{glob_match, PathString};
+
+fn path(path: &str) -> PathString {
+    PathString::from(path)
+}
+
+#[test]
+fn clean_removes_dots_and_keeps_the_root() {
+    assert_eq!(
+        path("/bin/./../home//notes/").clean().as_str(),
+        "/home/notes"
+    );
+    assert_eq!(path("bin/app.run").clean().as_str(), "/bin/app.run");
+    assert_eq!(path("/").clean().as_str(), "/");
+    assert_eq!(path("/bin/..").clean().as_str(), "/");
+    assert!(path("").clean().is_absolute());
+}
+
+#[test]
+fn join_replaces_with_absolute_paths() {
+    assert_eq!(path("/home").join(&"notes.txt").as_str(), "/home/notes.txt");
+    assert_eq!(path("/home").join(&"/bin").as_str(), "/bin");
+    assert_eq!(path("/").join(&"bin").as_str(), "/bin");
+}
+
+#[test]
+fn relative_to_goes_up_and_down() {
+    assert_eq!(
+        path("/home/notes.txt").relative_to(&"/bin").as_str(),
+        "../home/notes.txt"
+    );
+    assert_eq!(
+        path("/bin/app.run").relative_to(&"/").as_str(),
+        "bin/app.run"
+    );
+    assert_eq!(path("/bin").relative_to(&"/bin/").as_str(), ".");
+}
+
+#[test]
+fn strip_prefix_takes_whole_components() {
+    let app = path("/bin/app.run");
+    assert_eq!(app.strip_prefix(&path("/bin")).unwrap().as_str(), "app.run");
+    assert_eq!(
+        app.strip_prefix(&path("/bin/")).unwrap().as_str(),
+        "app.run"
+    );
+    assert_eq!(
+        app.strip_prefix(&path("/")).unwrap().as_str(),
+        "bin/app.run"
+    );
+    assert_eq!(app.strip_prefix(&app).unwrap().as_str(), "");
+    assert!(app.strip_prefix(&path("/bi")).is_none());
+    assert_eq!(path("bin").strip_prefix(&path("")).unwrap().as_str(), "bin");
+}
+
+#[test]
+fn with_extension_replaces_the_last_one() {
+    assert_eq!(
+        path("notes.txt").with_extension("for").as_str(),
+        "notes.for"
+    );
+    assert_eq!(
+        path("archive.tar.gz").with_extension("").as_str(),
+        "archive.tar"
+    );
+    assert_eq!(path("notes").with_extension("txt").as_str(), "notes.txt");
+    assert_eq!(
+        path(".hidden").with_extension("txt").as_str(),
+        ".hidden.txt"
+    );
+}
+
+#[test]
+fn glob_match_wildcards() {
+    assert!(glob_match("*.txt", "notes.txt"));
+    assert!(glob_match("n?tes.*", "notes.txt"));
+    assert!(glob_match("*", ""));
+    assert!(!glob_match("*.txt", "notes.for"));
+    assert!(!glob_match("?", ""));
+}
+
+#[test]
+fn glob_finds_files_below_directories() {
+    fs::start();
+    fs::create_dir(path("/globbed")).unwrap();
+    fs::create_dir(path("/globbed/deep")).unwrap();
+    for file in ["/globbed/a.txt", "/globbed/b.for", "/globbed/deep/c.txt"] {
+        fs::create_data_file(path(file), b"hej".to_vec()).unwrap();
+    }
+    let found = |pattern| {
+        let mut paths: Vec<_> = fs::glob(pattern)
+            .map(|item| String::from(item.path.as_str()))
+            .collect();
+        paths.sort();
+        paths
+    };
+    assert_eq!(found("/globbed/*.txt"), ["/globbed/a.txt"]);
+    assert_eq!(
+        found("/globbed/**/*.txt"),
+        ["/globbed/a.txt", "/globbed/deep/c.txt"]
+    );
+    assert_eq!(found("/globbed/b.for"), ["/globbed/b.for"]);
+}
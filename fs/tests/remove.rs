@@ -0,0 +1,19 @@
+use fs::{Attributes, FileSystemError};
+
+#[test]
+fn remove_keeps_directories_with_read_only_files_below() {
+    fs::start();
+    fs::create_dir(String::from("/project")).unwrap();
+    fs::create_dir(String::from("/project/notes")).unwrap();
+    fs::create_data_file(String::from("/project/notes/keep.txt"), b"hej".to_vec())
+        .unwrap()
+        .set_attributes(Attributes::READ_ONLY);
+    let result = fs::remove(String::from("/project"), true);
+    assert!(matches!(result, Err(FileSystemError::ReadOnly)));
+    assert!(fs::get_file(String::from("/project/notes/keep.txt")).is_ok());
+
+    fs::get_file_write(String::from("/project/notes/keep.txt"))
+        .unwrap()
+        .set_attributes(Attributes::NONE);
+    assert!(fs::remove(String::from("/project"), true).is_ok());
+}
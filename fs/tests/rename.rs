@@ -0,0 +1,41 @@
+use std::sync::Once;
+
+use fs::{Attributes, FileSystemError};
+
+static START: Once = Once::new();
+
+/// Every test shares the file system, so each one works in a directory of its own
+fn directory(name: &str) -> String {
+    START.call_once(fs::start);
+    let path = format!("/{name}");
+    fs::create_dir(&path).unwrap();
+    fs::create_data_file(format!("{path}/notes.txt"), b"hej".to_vec()).unwrap();
+    path
+}
+
+fn notes(path: &str) -> Vec<u8> {
+    fs::get_file(format!("{path}/notes.txt"))
+        .and_then(|file| file.read_file())
+        .unwrap()
+}
+
+#[test]
+fn rename_to_invalid_name_keeps_file() {
+    let path = directory("invalid");
+    let result = fs::rename(format!("{path}/notes.txt"), format!("{path}/bad?name"));
+    assert!(matches!(result, Err(FileSystemError::InvalidName)));
+    assert_eq!(notes(&path), b"hej");
+}
+
+#[test]
+fn rename_into_read_only_directory_keeps_file() {
+    let path = directory("read_only");
+    let locked = format!("{path}/locked");
+    fs::create_dir(&locked)
+        .unwrap()
+        .set_attributes(Attributes::READ_ONLY);
+    let result = fs::move_to(format!("{path}/notes.txt"), &locked);
+    assert!(matches!(result, Err(FileSystemError::ReadOnly)));
+    assert_eq!(notes(&path), b"hej");
+    assert!(fs::get_file(format!("{locked}/notes.txt")).is_err());
+}
@@ -0,0 +1,115 @@
+use alloc::{boxed::Box, format, vec, vec::Vec};
+use base::display::{DefaultVgaWriter, SavedScreen, VgaColor};
+use base::input::{CONSOLE_KEYS, CONSOLE_SWITCHER};
+use fs::PathString;
+
+/// How large the stack of every console but the first is, the first uses the boot stack
+const CONSOLE_STACK_SIZE: usize = 64 * 1024;
+
+/// A shell of its own with a forth machine, screen and active directory
+///
+/// Only the shown console runs, the others wait for a key where they were
+/// when another console was switched to
+struct Console {
+    stack_pointer: usize,
+    stack: Option<Box<[u8]>>,
+    screen: Option<SavedScreen>,
+    active_directory: PathString,
+}
+
+static mut CONSOLES: Vec<Console> = Vec::new();
+static mut ACTIVE_CONSOLE: usize = 0;
+
+// Saves the callee saved registers on the current stack, switches to
+// the other stack and restores the registers saved there
+core::arch::global_asm!(
+    ".global switch_stack",
+    "switch_stack:",
+    "push rbp",
+    "push rbx",
+    "push r12",
+    "push r13",
+    "push r14",
+    "push r15",
+    "mov [rdi], rsp",
+    "mov rsp, rsi",
+    "pop r15",
+    "pop r14",
+    "pop r13",
+    "pop r12",
+    "pop rbx",
+    "pop rbp",
+    "ret",
+);
+extern "C" {
+    fn switch_stack(old_stack_pointer: *mut usize, new_stack_pointer: usize);
+}
+
+/// Lets Alt+F1..F4 switch between consoles, the caller becomes the first console
+pub fn setup() {
+    unsafe {
+        CONSOLES = (0..CONSOLE_KEYS.len())
+            .map(|_| Console {
+                stack_pointer: 0,
+                stack: None,
+                screen: None,
+                active_directory: PathString::from("/"),
+            })
+            .collect();
+        CONSOLE_SWITCHER = Some(switch_to);
+    }
+}
+
+fn switch_to(index: usize) {
+    unsafe {
+        let previous = ACTIVE_CONSOLE;
+        if index == previous || index >= CONSOLES.len() {
+            return;
+        }
+        let mut writer = DefaultVgaWriter::new_unsafe();
+        CONSOLES[previous].screen = Some(writer.save_screen());
+        CONSOLES[previous].active_directory = fs::active_directory();
+
+        let next = &mut CONSOLES[index];
+        if next.stack.is_none() && index != 0 {
+            next.stack_pointer =
+                prepare_stack(next.stack.insert(vec![0; CONSOLE_STACK_SIZE].into()));
+        }
+        match &next.screen {
+            Some(screen) => writer.restore_screen(screen),
+            None => writer.clear_screen(VgaColor::Black),
+        };
+        fs::set_active_directory(next.active_directory.clone());
+        ACTIVE_CONSOLE = index;
+
+        let next_stack_pointer = next.stack_pointer;
+        switch_stack(&mut CONSOLES[previous].stack_pointer, next_stack_pointer);
+    }
+}
+
+/// Lays out a new stack as if `switch_stack` had left it, so switching to it starts the console
+fn prepare_stack(stack: &mut [u8]) -> usize {
+    let top = (stack.as_mut_ptr() as usize + stack.len()) & !0xF;
+    // a return address for console_start which is never used, and where switch_stack returns to
+    let frame = [0, 0, 0, 0, 0, 0, console_start as usize, 0];
+    let stack_pointer = top - frame.len() * core::mem::size_of::<usize>();
+    unsafe {
+        core::ptr::copy_nonoverlapping(frame.as_ptr(), stack_pointer as *mut usize, frame.len())
+    };
+    stack_pointer
+}
+
+extern "C" fn console_start() -> ! {
+    let console = unsafe { ACTIVE_CONSOLE };
+    let mut forth_machine = crate::new_forth_machine();
+    forth_machine
+        .formatter
+        .write_str(&format!(
+            "Console {}, Alt+F1..F{} switches console",
+            console + 1,
+            CONSOLE_KEYS.len()
+        ))
+        .next_line();
+    builtins::load_session_words(&mut forth_machine);
+    crate::shell(forth_machine)
+}
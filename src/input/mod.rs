@@ -1,4 +1,7 @@
+use core::sync::atomic::Ordering;
+
 use base::{
+    forth::{INTERRUPTIBLE, INTERRUPT_REQUESTED},
     input::{ ALT_MODIFIER, CTRL_MODIFIER, KEYBOARD_QUEUE, SHIFT_MODIFIER, ScanCode, KeyEvent},
     pic::pics,
 };
@@ -81,6 +84,11 @@ unsafe fn process_data(data: u8) {
                 (data as usize & 0b_0111_1111) | addon
             };
             
+            // Ctrl+C stops whatever forth is running, and isn't read as a key afterwards
+            if pressed && KEYBOARD_STATE.ctrl_pressed && key == 0x2E && INTERRUPTIBLE.load(Ordering::Relaxed) {
+                INTERRUPT_REQUESTED.store(true, Ordering::Relaxed);
+                return;
+            }
             match pressed {
                 true => KEYBOARD_QUEUE.insert(KeyEvent::KeyPressed { modifiers: KEYBOARD_STATE.get_modifier_usize().into(), key: ScanCode::new(key as usize) }),
                 false => KEYBOARD_QUEUE.insert(KeyEvent::KeyReleased { key: ScanCode::new(key as usize) }),
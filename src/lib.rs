@@ -14,12 +14,13 @@ extern crate alloc;
 use alloc::boxed::Box;
 use base::display::{DefaultVgaWriter, UniversalVgaFormatter, VgaColorCombo, VgaPalette, VgaColor};
 use base::forth::{ForthMachine, StackItem};
-use base::input::KEYBOARD_QUEUE;
+use base::line_editor::LineEditor;
+use builtins::ShellCompleter;
 use easter_eggs::SplashScreen;
 use forth::Stack;
 
 use base::*;
-use fs::{FileSystemError, PathString};
+use fs::FileSystemError;
 use interrupt::setup::GLOBAL_OS_TIME;
 
 use crate::interrupt::pitinit;
@@ -35,6 +36,8 @@ use x86_64::instructions::port::PortWriteOnly;
 
 pub mod cpuid;
 
+mod console;
+
 mod easter_eggs;
 mod panic;
 use crate::multiboot_info::MultibootInfoHeader;
@@ -75,94 +78,121 @@ pub extern "C" fn rust_start(info: u64) -> ! {
 
     // Starta filsystemet och installera alla inbyggda appar
     {
+        unsafe { fs::CLOCK = Some(uptime_seconds) };
         fs::start();
         builtins::install_all().unwrap();
         fs::install_app::<SplashScreen>().unwrap();
     }
 
-    // Skapa Forths runtime
-    let mut forth_machine = ForthMachine::default();
+    // packa upp initrd-modulen som GRUB laddat i filsystemet, dess filer ersätter de inbyggda
+    let initrd = multiboot_info
+        .initrd()
+        .map(|module| fs::unpack_tar(unsafe { module.bytes() }, fs::PathString::from("/")));
 
-    // definera ordet run
-    forth_machine.insert_default_word("run", &run);
+    // Skapa Forths runtime
+    let mut forth_machine = new_forth_machine();
+    if let Some(Err(error)) = initrd {
+        forth_machine
+            .formatter
+            .write_str(&format!("Could not unpack the initrd: {error:?}"))
+            .next_line();
+    }
     
     // starta bin/startup.for
-    forth_machine.add_instructions_to_end(&"\"bin/startup.for\" \"forrunner\" run");
+    forth_machine.add_instructions_to_end(&"\"bin/startup.for\" \"forrunner\" ?run");
     forth_machine.run_to_end();
 
-    // kör terminalen (operativsystemet är helt startat)
-    unsafe {
-        let mut string = String::new();
-        forth_machine.formatter.enable_cursor().set_position((0, 7));
-        loop {
-            forth_machine
-                .formatter
-                .set_default_colors(VgaColorCombo::on_black(VgaColor::White))
-                .enable_cursor()
-                .write_str(fs::active_directory().as_str())
-                .write_str(" > ");
-            loop {
-                let c: char = KEYBOARD_QUEUE.getch_blocking();
-                let count = string.chars().count();
-                match c {
-                    '\x08' => {
-                        forth_machine
-                            .formatter
-                            .back_up(count)
-                            .write_str(&" ".repeat(count))
-                            .back_up(count);
-                        string.pop();
-                    }
-                    '\n' => {
-                        forth_machine.formatter.next_line();
-                        let mut new_string = String::new();
-                        core::mem::swap(&mut new_string, &mut string);
-                        forth_machine.add_instructions_to_end(&new_string);
-                        forth_machine.run_to_end();
-                        forth_machine.formatter.next_line();
-
-                        break;
-                    },
-                    '\0' => {
-                        forth_machine.formatter.back_up(count);
-                    },
-                    _ => {
-                        forth_machine.formatter.back_up(count);
-                        string.push(c);
-                    }
-                }
-                forth_machine.formatter.write_str(&string);
+    // ladda sparade ord från sessionens definitionsfil
+    builtins::load_session_words(&mut forth_machine);
+
+    // kör terminalen (operativsystemet är helt startat), Alt+F1..F4 byter konsol
+    console::setup();
+    forth_machine.formatter.enable_cursor().set_position((0, 7));
+    shell(forth_machine)
+}
+
+/// En forth-maskin med ordet run och orden som behöver filsystemet
+fn new_forth_machine() -> ForthMachine {
+    let mut forth_machine = ForthMachine::default();
+    forth_machine.insert_default_word("run", &run);
+    forth_machine.insert_default_word("?run", &run_or_abort);
+    builtins::install_words(&mut forth_machine);
+    forth_machine
+}
+
+/// Terminalen, varje konsol kör en egen
+fn shell(mut forth_machine: ForthMachine) -> ! {
+    let mut line_editor = LineEditor::new();
+    line_editor.load_history(builtins::load_history());
+    loop {
+        forth_machine
+            .formatter
+            .set_default_colors(VgaColorCombo::on_black(VgaColor::White))
+            .enable_cursor();
+        let prompt = builtins::expand_prompt(&forth_machine, uptime_seconds());
+        let completer = ShellCompleter::new(&forth_machine);
+        let line = line_editor.read_line(&mut forth_machine.formatter, prompt, &completer);
+        builtins::append_history(&line);
+        let line = builtins::expand_alias(&line);
+        // kommandon som `help eng` och `dir | grep for` körs som appar, allt annat körs som forth
+        match builtins::Pipeline::parse(&line, &forth_machine) {
+            Some(pipeline) => pipeline.run(&mut forth_machine),
+            None => {
+                forth_machine.add_instructions_to_end(&line);
+                forth_machine.run_to_end();
             }
         }
-    };
+        forth_machine.formatter.next_line();
+    }
+}
+
+/// Sekunder sedan datorn startade, timern tickar med PIT_FREQUENCY / 2400 Hz
+fn uptime_seconds() -> u64 {
+    const PIT_FREQUENCY: u64 = 1_193_182;
+    unsafe { GLOBAL_OS_TIME * 2400 / PIT_FREQUENCY }
 }
 
+// "app" RUN, kör appen och lägger dess statuskod på stacken, 0 om den lyckades
 fn run(machine: &mut ForthMachine) {
-    let mut app = match get_app(machine) {
-        Ok(app) => app,
-        Err(err) => {
-            machine.formatter.write_str(&format!("Run: {err:?} "));
-            return;
+    let code = match try_run(machine) {
+        Ok(()) => 0,
+        Err((code, message)) => {
+            machine.error(&message);
+            code
         }
     };
-    match app.run(machine) {
-        Ok(()) => (),
-        Err(err) => {
-            machine
-                .formatter
-                .write_str(&format!("App: {err:?} "));
-        }
+    machine.stack.push(StackItem::Int(code));
+}
+
+// "app" ?RUN, kör appen och avbryter allt som körs om den misslyckas
+fn run_or_abort(machine: &mut ForthMachine) {
+    if let Err((_, message)) = try_run(machine) {
+        machine.abort(&message);
     }
 }
+
+/// Kör appen och sparar statuskoden i STATUS
+///
+/// Koderna kommer från ProgramError::code och FileSystemError::code
+fn try_run(machine: &mut ForthMachine) -> Result<(), (isize, String)> {
+    let result = match get_app(machine) {
+        Ok(mut app) => machine
+            .start_app(|machine| app.run(machine))
+            .map_err(|err| (err.code(), format!("App: {err:?}"))),
+        Err(err) => Err((err.code(), format!("Run: {err:?}"))),
+    };
+    let code = result.as_ref().err().map_or(0, |(code, _)| *code);
+    machine.set_variable("STATUS", &format!("{code}"));
+    result
+}
 fn get_app(machine: &mut ForthMachine) -> Result<Box<dyn LittleManApp>, FileSystemError> {
     let path = machine
         .stack
         .try_pop::<String>()
         .ok_or(FileSystemError::EmptyPath)?;
-    let finalized_path = PathString::from(path).add_extension("run");
-    let file = fs::get_file_relative(&finalized_path)
-        .or(fs::get_file(PathString::from("bin").append(&finalized_path)))?;
-    let app = file.launch_app()?;
+    let app_path =
+        builtins::find_app(machine, &path).ok_or(FileSystemError::FileNotFound("no app with that name"))?;
+    let app = fs::get_file(app_path)?.launch_app()?;
     Ok(app)
 }
 
@@ -112,6 +112,8 @@ impl FrameAllocator for ElfTrustAllocator {
             self.next_free_frame = MemoryFrame(self.kernel.end_frame + 1);
         } else if self.multiboot.contains(&frame) {
             self.next_free_frame = MemoryFrame(self.multiboot.end_frame + 1);
+        } else if let Some(initrd) = self.initrd.as_ref().filter(|initrd| initrd.contains(&frame)) {
+            self.next_free_frame = MemoryFrame(initrd.end_frame + 1);
         } else {
             self.next_free_frame.0 += 1;
             self.available_frames -= 1;
@@ -145,13 +147,14 @@ impl ElfTrustAllocator {
     pub fn new(
         kernel: FrameRangeInclusive,
         multiboot: FrameRangeInclusive,
+        initrd: Option<FrameRangeInclusive>,
         areas: MemoryAreaIter,
     ) -> Self {
         let available_frames = {
             let total = areas
                 .clone()
                 .fold(0usize, |acc, entry| (entry.length as usize / 4096) + acc);
-            total - kernel.span() - multiboot.span()
+            total - kernel.span() - multiboot.span() - initrd.as_ref().map_or(0, |initrd| initrd.span())
         };
         let mut ourself = Self {
             next_free_frame: MemoryFrame::inside_address(0),
@@ -159,6 +162,7 @@ impl ElfTrustAllocator {
             areas,
             multiboot,
             kernel,
+            initrd,
             available_frames,
         };
         ourself.choose_next_area();
@@ -130,6 +130,7 @@ pub struct ElfTrustAllocator {
     areas: MemoryAreaIter,
     multiboot: FrameRangeInclusive,
     kernel: FrameRangeInclusive,
+    initrd: Option<FrameRangeInclusive>,
 }
 /// An Iterator over the computers memory areas
 #[derive(Clone)]
@@ -167,7 +168,9 @@ pub(crate) fn remap_everything(
     let kernel = unsafe { elf_tag.frame_range() };
     let multiboot2 = unsafe { info.frame_range() };
 
-    let mut allocator = ElfTrustAllocator::new(kernel, multiboot, memory_tag.area_iter());
+    let initrd = info.initrd().map(|module| unsafe { module.frame_range() });
+    let mut allocator =
+        ElfTrustAllocator::new(kernel, multiboot, initrd, memory_tag.area_iter());
     let mut temp_page = TemporaryPage::new(MemoryPage::inside_address(0xefaceea7), &mut allocator);
 
     let mut new_table = {
@@ -198,6 +201,17 @@ pub(crate) fn remap_everything(
         for frame in multiboot2 {
             mapper.identity_map(frame, EntryFlags::PRESENT, &mut allocator);
         }
+        //map the initrd, its files are read straight from it
+        //it can share its first or last frame with the kernel or the multiboot info, those are already mapped
+        if let Some(module) = info.initrd() {
+            let (kernel, multiboot) = unsafe { (elf_tag.frame_range(), info.frame_range()) };
+            for frame in unsafe { module.frame_range() } {
+                if kernel.contains(&frame) || multiboot.contains(&frame) {
+                    continue;
+                }
+                mapper.identity_map(frame, EntryFlags::PRESENT, &mut allocator);
+            }
+        }
         //map vga buffer
         for i in 0xA0..0xBF {
             let vga_buffer_frame = MemoryFrame::inside_address(i * 0x1000);
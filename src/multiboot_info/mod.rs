@@ -1,7 +1,9 @@
 pub mod elf;
 pub mod memory_map;
+pub mod module;
 use elf::*;
 use memory_map::*;
+use module::*;
 
 use core::{mem::size_of, str::from_utf8};
 
@@ -55,6 +57,15 @@ impl MultibootInfo {
     pub fn tag_iter(&self) -> MultiBootTagIter {
         MultiBootTagIter::new(*self)
     }
+    /// The module loaded with `module2 /boot/initrd.tar initrd` in grub.cfg, if there is one
+    pub fn initrd(&self) -> Option<ModuleTag> {
+        self.tag_iter().find_map(|tag| match tag {
+            MultiBootTag::Module(module) if module.command_line() == "initrd" && !module.is_empty() => {
+                Some(module)
+            }
+            _ => None,
+        })
+    }
     pub unsafe fn frame_range(&self) -> FrameRangeInclusive {
         FrameRangeInclusive::new(
             MemoryFrame::inside_address(self.pointer),
@@ -73,6 +84,7 @@ impl<'a> KernelDebug<'a> for MultiBootTag {
             MultiBootTag::BasicMem(_) => todo!(),
             MultiBootTag::BootoaderName(map) => formatter.debug_str(map.as_str()),
             MultiBootTag::ElfSymbols(map) => map.debug(formatter),
+            MultiBootTag::Module(module) => module.debug(formatter),
             MultiBootTag::End => formatter.debug_str("end"),
         }
     }
@@ -127,7 +139,10 @@ impl Iterator for MultiBootTagIter {
                 TagType::BiosBootDevice => (),
                 TagType::ApmTable => (),
                 TagType::VbeInfo => (),
-                TagType::Module => (),
+                TagType::Module => {
+                    let info = unsafe { ModuleTag::from_ref(&tag_head) };
+                    return Some(MultiBootTag::Module(info));
+                }
             }
             //rounds upward to nearest multiple of 8
         }
@@ -143,6 +158,7 @@ pub enum MultiBootTag {
     BasicMem(BasicMemoryTag),
     BootoaderName(BootloaderNameTag),
     ElfSymbols(ElfSymbolTag),
+    Module(ModuleTag),
     End,
 }
 impl MultiBootTag {
@@ -152,6 +168,7 @@ impl MultiBootTag {
             MultiBootTag::BootoaderName(_) => TagType::BootoaderName,
             MultiBootTag::ElfSymbols(_) => TagType::ElfSymbol,
             MultiBootTag::BasicMem(_) => TagType::BasicMemoryTag,
+            MultiBootTag::Module(_) => TagType::Module,
             MultiBootTag::End => TagType::End,
         }
     }
@@ -0,0 +1,66 @@
+use core::{mem::size_of, slice::from_raw_parts, str::from_utf8};
+
+use crate::{
+    display::{KernelDebug, KernelFormatter},
+    memory::frame::{FrameRangeInclusive, MemoryFrame},
+};
+
+use super::{transmute, type_after, TagHeader};
+
+/// A file GRUB loaded into memory next to the kernel, from a `module2` line in grub.cfg
+#[repr(C)]
+pub struct ModuleTagHeader {
+    head: TagHeader,
+    module_start: u32,
+    module_end: u32,
+}
+
+pub struct ModuleTag {
+    header: &'static ModuleTagHeader,
+    command_line: &'static str,
+}
+
+impl ModuleTag {
+    pub unsafe fn from_ref(head: &'static TagHeader) -> Self {
+        let header: &'static ModuleTagHeader = &*transmute(head as *const TagHeader);
+        let pointer: *const u8 = type_after(header as *const ModuleTagHeader);
+        let string_len = (head.size as usize - size_of::<ModuleTagHeader>()).saturating_sub(1);
+        let command_line = from_utf8(from_raw_parts(pointer, string_len)).unwrap_or("");
+        Self {
+            header,
+            command_line,
+        }
+    }
+    /// What's after the path on the `module2` line, like `initrd`
+    pub fn command_line(&self) -> &'static str {
+        self.command_line
+    }
+    pub fn is_empty(&self) -> bool {
+        self.header.module_end <= self.header.module_start
+    }
+    /// The frames the module is in, which may not be handed out and have to be mapped before it's read
+    pub unsafe fn frame_range(&self) -> FrameRangeInclusive {
+        FrameRangeInclusive::new(
+            MemoryFrame::inside_address(self.header.module_start as u64),
+            MemoryFrame::inside_address(self.header.module_end.max(self.header.module_start + 1) as u64 - 1),
+        )
+    }
+    /// The contents of the module
+    ///
+    /// Its frames have to be identity mapped, which `remap_everything` does
+    pub unsafe fn bytes(&self) -> &'static [u8] {
+        let length = self.header.module_end.saturating_sub(self.header.module_start);
+        from_raw_parts(self.header.module_start as u64 as *const u8, length as usize)
+    }
+}
+
+impl<'a> KernelDebug<'a> for ModuleTag {
+    fn debug(&self, formatter: KernelFormatter<'a>) -> KernelFormatter<'a> {
+        formatter
+            .debug_struct("ModuleTag")
+            .debug_field("start", &self.header.module_start)
+            .debug_field("end", &self.header.module_end)
+            .debug_field("command line", &self.command_line)
+            .finish()
+    }
+}